@@ -0,0 +1,67 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, Result, Token, Type};
+
+use crate::attr::Options;
+use crate::expand::{self, Target};
+
+/// The input to `alias_lit!`, e.g. `headers: Headers -> insert`
+pub struct Alias {
+    macro_name: Ident,
+    ty: Type,
+    method: Ident,
+}
+
+impl Parse for Alias {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let macro_name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        input.parse::<Token![->]>()?;
+        let method = input.parse()?;
+
+        Ok(Alias {
+            macro_name,
+            ty,
+            method,
+        })
+    }
+}
+
+impl Alias {
+    pub fn expand(&self) -> TokenStream {
+        let ty = &self.ty;
+        let target = Target {
+            path: quote!(<#ty>),
+            generic: None,
+            wrap: None,
+            extend: false,
+        };
+        let method = &self.method;
+        let method = quote!(#method);
+
+        // the method may take either an element or a key and value so both forms are accepted, with
+        // anything that isn't a plain map literal left to the element arms
+        let options = Options::default();
+        let map_body = expand::map_body(&target, &method, &options, false);
+        let elem_body = expand::elem_body(&target, &method, &options);
+
+        // an alias already named like its macro would clash with the import
+        let import = match ty {
+            Type::Path(path) => !path.path.is_ident(&self.macro_name),
+            _ => true,
+        };
+        expand::macro_def(
+            &self.macro_name,
+            quote! {
+                #map_body
+                #elem_body
+            },
+            import,
+            false,
+        )
+        .into_iter()
+        .collect()
+    }
+}
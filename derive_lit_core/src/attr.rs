@@ -0,0 +1,540 @@
+use proc_macro2::Span;
+use syn::ext::IdentExt;
+use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, ExprRange, Ident, Lit, LitInt, LitStr, Meta, NestedMeta, Path, Result, Token, Type,
+    UseTree, WherePredicate,
+};
+
+use crate::check;
+use crate::expand::Kind;
+
+/// Options collected from the `#[lit(...)]` attributes on the deriving type
+#[derive(Default)]
+pub struct Options {
+    /// A `#[cfg]` predicate wrapped around everything the derive generates
+    pub cfg: Option<Meta>,
+    /// The name of the macro, in place of the snake case of the type's name
+    pub rename: Option<Ident>,
+    /// Whether an acronym is merged with the word after it when snake casing, as in `httpserver_pool`
+    pub merge_acronyms: bool,
+    /// The path from the crate root to the module of the type, for macros exported with `#[lit(export)]`
+    pub export: Option<Vec<Ident>>,
+    /// Whether exported macros are re-exported from the module of the type, under hidden names at the crate root
+    pub reexport: bool,
+    /// The number of elements that fit without allocating, for picking between `new()` and `with_capacity(n)`
+    pub inline_capacity: Option<LitInt>,
+    /// Whether literal histogram buckets are checked to be contiguous and non-overlapping
+    pub contiguous: bool,
+    /// The method that sparse matrix literals written as `[A, B; C, D]` set each block with, by its row and column
+    pub blocks: Option<Ident>,
+    /// Whether segment tree literals also give their number of leaves as the tree's const parameter
+    pub const_len: bool,
+    /// The total that literal weights must sum to
+    pub weight_sum: Option<Lit>,
+    /// Whether the literal starts with `args...;` forwarded to `new(args...)`
+    pub new_args: bool,
+    /// Whether the literal can start with `in alloc;`, constructing with `new_in(alloc)`
+    pub new_in: bool,
+    /// Whether a literal starting with `from base;` clones `base` instead of moving it
+    pub clone_seed: bool,
+    /// Whether the constructor of a `ChannelLit` returns only the sender, instead of a `(tx, rx)` pair
+    pub sender: bool,
+    /// Whether the constructors are `async` and awaited by the literal
+    pub async_new: bool,
+    /// The constructor used by literals without a keyword, in place of `new`, which is `default` with `#[lit(default)]`
+    pub default_ctor: Option<Ident>,
+    /// The function making the hasher that literals construct with, through `with_hasher` in place of `new`
+    pub hasher: Option<Path>,
+    /// Further constructors as `(method, keyword)`, used by literals starting with the keyword
+    pub ctors: Vec<(Ident, Ident)>,
+    /// Whether literal elements, or the literal keys of a map, are sorted at compile time
+    pub sort: bool,
+    /// The bulk constructor that literals of only literal elements or keys are sorted into, instead of being added one by
+    /// one
+    pub bulk: Option<Ident>,
+    /// Whether elements are added in the reverse of the order they're written
+    pub reverse: bool,
+    /// Whether elements are `Option`s that are only added when `Some`
+    pub skip_none: bool,
+    /// Whether set literals panic on an element the set already contains
+    pub strict: bool,
+    /// The method given each element together with the `Location` of its literal, in place of the derive's usual method
+    pub track_caller: Option<Ident>,
+    /// The `const fn` that literal elements are checked with at compile time
+    pub validate: Option<Path>,
+    /// The range that integer literal elements must be within
+    pub range: Option<ExprRange>,
+    /// The `NonZero` type that integer literal elements are converted to through `new`
+    pub non_zero: Option<Type>,
+    /// Setters as `(name, method)`, called with the values of the `name: val` pairs a literal may start with
+    pub setters: Vec<(Ident, Ident)>,
+    /// The push methods of the columns of a struct-of-arrays, in the order of the fields of its rows
+    pub columns: Vec<Ident>,
+    /// Labeled sections as `(label, method)`, replacing the derive's usual literal syntax
+    pub sections: Vec<(Ident, Ident)>,
+    /// The method given the value of a trailing `_ => val` map entry
+    pub fallback: Option<Ident>,
+    /// Whether elements are wrapped in `Cow`, as `Cow::Borrowed` for string literals and `Cow::Owned` for the others
+    pub cow: bool,
+    /// Whether string literal chunks of several lines are added line by line
+    pub split_lines: bool,
+    /// Whether byte string elements add each of their bytes, instead of being added as they are
+    pub splat: bool,
+    /// The method, `chars` or `bytes`, that string literal elements are split with into the elements they add
+    pub splat_str: Option<Ident>,
+    /// The `Copy` element type of literals whose elements are kept in a static array, unless they are `strict`
+    pub copy: Option<Type>,
+    /// The macro that elements are passed to as tokens, instead of being parsed as expressions
+    pub tokens: Option<Path>,
+    /// What map literals do when a key is inserted twice
+    pub on_duplicate: Duplicate,
+    /// Whether a `_push` macro is also generated, for adding to an existing value
+    pub push_macro: bool,
+    /// The method that the entries of the `_map` macro are inserted with, which is only generated when this is given
+    pub map_macro: Option<Ident>,
+    /// The method iterating over the elements, or the `(key, val)` pairs of a map, that the `assert_..._eq` macro
+    /// compares, which is only generated when this is given
+    pub assert_eq: Option<Ident>,
+    /// The method given each record of a `csv "..."` literal as an array of its fields, which are only accepted when this
+    /// is given
+    pub csv: Option<Ident>,
+    /// The fallible method used by the `try_` macro, which is only generated when this is given
+    pub try_method: Option<Ident>,
+    /// The element type of the `TryFrom<Vec<T>>` and `TryFrom<&[T]>` impls, which are only generated when this is given
+    pub try_from: Option<Type>,
+    /// The most elements the `TryFrom` impls accept
+    pub capacity: Option<LitInt>,
+    /// The element type, or the key and value types of a map, that the `FromStr` impl parses, which is only generated
+    /// when this is given
+    pub from_str: Option<(Type, Option<Type>)>,
+    /// The element type, or key and value types, of the `from_elems` or `from_entries` function generated in place of
+    /// the macro
+    pub fn_ctor: Option<(Type, Option<Type>)>,
+    /// The element type of the generated `from_slice` function
+    pub from_slice: Option<Type>,
+    /// The key and value types of the generated `from_pairs` function
+    pub from_pairs: Option<(Type, Type)>,
+    /// The element type, or key and value types, of the `LitNew` and `LitPush` or `LitInsert` impls, which literals
+    /// then construct and add through
+    pub traits: Option<(Type, Option<Type>)>,
+    /// The method iterating over the elements, or the `(key, val)` pairs of a map, that `to_lit_string` renders, which
+    /// is only generated when this is given
+    pub to_lit_string: Option<Ident>,
+    /// Further `where` predicates of the `to_lit_string` impl, like the `Debug` bounds of type parameters
+    pub bounds: Vec<WherePredicate>,
+    /// The paths imported inside the expansion of literals, like the variants of an enum so keys can name them alone
+    pub uses: Vec<UseTree>,
+    /// The constructor that the version keys of `VersionMapLit` literals are converted with, given the major, minor and
+    /// patch numbers
+    pub version: Option<Path>,
+    /// The macro, like `eprintln`, that literals log each element or entry they add with in debug builds
+    pub trace: Option<Path>,
+    /// Whether map literals accept `k1, k2 => val` entries, inserted with the tuple `(k1, k2)` as the key
+    pub composite_keys: bool,
+    /// Whether the string literal keys of map literals are converted with `ToOwned`
+    pub own_keys: bool,
+    /// Whether the keys of map literals are converted with `Into`
+    pub key_into: bool,
+    /// The tuple struct or function that the keys of map literals are wrapped in, like `OrderedFloat`
+    pub key_wrap: Option<Path>,
+    /// The function constructing the nested maps that map literals split dotted string keys like `"server.port"` into
+    pub dotted: Option<Path>,
+    /// Whether the values of map literals are converted with `Into`
+    pub value_into: bool,
+    /// The function that the values of `include_json` entries are converted with
+    pub json_value: Option<Path>,
+    /// The derive named by the `#[lit(...)]` of an `EnumLit` variant, like `VecLit`, for the literal of its collection
+    pub kind: Option<Kind>,
+    /// The kind named by `#[lit(kind = "...")]`, for `#[derive(Lit)]` and the variants of an `EnumLit`
+    pub lit_kind: Option<(LitStr, Kind)>,
+    /// Whether map literals accept `key => ..iter` entries, inserting every item of `iter` under `key`
+    pub multi: bool,
+}
+
+/// What a map literal does when a key is inserted twice
+#[derive(Clone, Copy, Default)]
+pub enum Duplicate {
+    /// Keep the first value, skipping entries whose key the map already contains
+    First,
+    /// Keep the last value, which is what inserting does anyway
+    #[default]
+    Last,
+    /// Panic with the key's `Debug` output
+    Panic,
+}
+
+impl Duplicate {
+    pub fn from_ident(ident: &Ident) -> Result<Self> {
+        match ident.to_string().as_str() {
+            "first" => Ok(Duplicate::First),
+            "last" => Ok(Duplicate::Last),
+            "panic" => Ok(Duplicate::Panic),
+            _ => Err(syn::Error::new(
+                ident.span(),
+                "expected `first`, `last` or `panic`",
+            )),
+        }
+    }
+
+    pub fn ident(self) -> Ident {
+        let name = match self {
+            Duplicate::First => "first",
+            Duplicate::Last => "last",
+            Duplicate::Panic => "panic",
+        };
+        Ident::new(name, Span::call_site())
+    }
+}
+
+impl Options {
+    /// Collects the options from `attrs`, along with the errors of any that are malformed
+    ///
+    /// A malformed option is left at its default, so the macro can still be generated and the error isn't buried under
+    /// failing invocations of a missing macro.
+    pub fn from_attrs(attrs: &[Attribute]) -> (Self, Option<syn::Error>) {
+        let mut options = Options::default();
+        let mut errors: Option<syn::Error> = None;
+        let mut push = |err| match &mut errors {
+            Some(errors) => errors.combine(err),
+            None => errors = Some(err),
+        };
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("lit")) {
+            let list = match attr.parse_meta() {
+                Ok(Meta::List(list)) => list,
+                Ok(meta) => {
+                    push(syn::Error::new_spanned(meta, "expected `#[lit(...)]`"));
+                    continue;
+                }
+                Err(err) => {
+                    push(err);
+                    continue;
+                }
+            };
+
+            for nested in list.nested {
+                let result = match nested {
+                    NestedMeta::Meta(meta) => options.apply(&meta),
+                    nested => Err(syn::Error::new_spanned(nested, "expected an option")),
+                };
+                if let Err(err) = result {
+                    push(err);
+                }
+            }
+        }
+
+        if options.new_args {
+            if let Some(capacity) = options.inline_capacity.take() {
+                push(syn::Error::new_spanned(
+                    capacity,
+                    "`inline_capacity` can't be combined with `new_args`",
+                ));
+            }
+        }
+
+        if options.new_args || options.default_ctor.is_some() {
+            if let Some(hasher) = options.hasher.take() {
+                push(syn::Error::new_spanned(
+                    hasher,
+                    "`hasher` can't be combined with `new_args` or another default constructor",
+                ));
+            }
+        }
+
+        (options, errors)
+    }
+
+    /// Sets the option given by `meta`
+    fn apply(&mut self, meta: &Meta) -> Result<()> {
+        let key = meta
+            .path()
+            .get_ident()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+
+        match (key.as_str(), meta) {
+            ("cfg", Meta::NameValue(nv)) => {
+                self.cfg = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("rename", Meta::NameValue(nv)) => {
+                self.rename = Some(lit_str(&nv.lit)?.parse_with(Ident::parse_any)?);
+            }
+            ("acronyms", Meta::NameValue(nv)) => {
+                let acronyms = lit_str(&nv.lit)?;
+                self.merge_acronyms = match acronyms.value().as_str() {
+                    "split" => false,
+                    "merge" => true,
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            acronyms,
+                            "expected `split` or `merge`",
+                        ))
+                    }
+                };
+            }
+            ("export", Meta::Path(_)) => self.export = Some(vec![]),
+            ("export", Meta::NameValue(nv)) => {
+                let module: Path = lit_str(&nv.lit)?.parse()?;
+                let relative = matches!(
+                    module.segments.first(),
+                    Some(segment) if segment.ident == "crate" || segment.ident == "self" || segment.ident == "super"
+                );
+                if module.leading_colon.is_some() || relative {
+                    return Err(syn::Error::new_spanned(
+                        &nv.lit,
+                        "expected the path of the module from the crate root, like `shapes::polygons`",
+                    ));
+                }
+                self.export = Some(module.segments.into_iter().map(|segment| segment.ident).collect());
+            }
+            ("reexport", Meta::Path(_)) => self.reexport = true,
+            ("inline_capacity", Meta::NameValue(nv)) => {
+                self.inline_capacity = Some(lit_int(&nv.lit)?.clone());
+            }
+            ("contiguous", Meta::Path(_)) => self.contiguous = true,
+            ("const_len", Meta::Path(_)) => self.const_len = true,
+            ("blocks", Meta::NameValue(nv)) => {
+                self.blocks = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("new_args", Meta::Path(_)) => self.new_args = true,
+            ("async_new", Meta::Path(_)) => self.async_new = true,
+            ("new_in", Meta::Path(_)) => self.new_in = true,
+            ("clone_seed", Meta::Path(_)) => self.clone_seed = true,
+            ("sender", Meta::Path(_)) => self.sender = true,
+            ("push_macro", Meta::Path(_)) => self.push_macro = true,
+            ("map_macro", Meta::Path(path)) => {
+                self.map_macro = Some(Ident::new("insert", path.span()));
+            }
+            ("map_macro", Meta::NameValue(nv)) => {
+                self.map_macro = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("skip_none", Meta::Path(_)) => self.skip_none = true,
+            ("sort", Meta::Path(_)) => self.sort = true,
+            ("bulk", Meta::NameValue(nv)) => {
+                self.bulk = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("reverse", Meta::Path(_)) => self.reverse = true,
+            ("strict", Meta::Path(_)) => self.strict = true,
+            ("cow", Meta::Path(_)) => self.cow = true,
+            ("split_lines", Meta::Path(_)) => self.split_lines = true,
+            ("splat", Meta::Path(_)) => self.splat = true,
+            ("splat", Meta::NameValue(nv)) => {
+                let method = lit_str(&nv.lit)?;
+                match method.value().as_str() {
+                    "chars" | "bytes" => {
+                        self.splat = true;
+                        self.splat_str = Some(method.parse()?);
+                    }
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            method,
+                            "expected `chars` or `bytes`",
+                        ))
+                    }
+                }
+            }
+            ("multi", Meta::Path(_)) => self.multi = true,
+            ("composite_keys", Meta::Path(_)) => self.composite_keys = true,
+            ("own_keys", Meta::Path(_)) => self.own_keys = true,
+            ("key_into", Meta::Path(_)) => self.key_into = true,
+            ("dotted", Meta::NameValue(nv)) => {
+                self.dotted = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("key_wrap", Meta::NameValue(nv)) => {
+                self.key_wrap = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("value_into", Meta::Path(_)) => self.value_into = true,
+            ("trace", Meta::Path(_)) => self.trace = Some(syn::parse_quote!(::std::eprintln)),
+            ("trace", Meta::NameValue(nv)) => {
+                self.trace = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("on_duplicate", Meta::NameValue(nv)) => {
+                self.on_duplicate = Duplicate::from_ident(&lit_str(&nv.lit)?.parse()?)?;
+            }
+            ("kind", Meta::NameValue(nv)) => {
+                let name = lit_str(&nv.lit)?;
+                self.lit_kind = Some((name.clone(), Kind::from_name(name)?));
+            }
+            ("non_zero", Meta::NameValue(nv)) => {
+                self.non_zero = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("copy", Meta::NameValue(nv)) => {
+                self.copy = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("tokens", Meta::NameValue(nv)) => {
+                self.tokens = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("try_from", Meta::NameValue(nv)) => {
+                self.try_from = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("from_str", Meta::NameValue(nv)) => {
+                self.from_str = Some(lit_str(&nv.lit)?.parse_with(elem_types)?);
+            }
+            ("from_slice", Meta::NameValue(nv)) => {
+                self.from_slice = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("from_pairs", Meta::NameValue(nv)) => {
+                self.from_pairs = Some(lit_str(&nv.lit)?.parse_with(|input: ParseStream| {
+                    let key = input.parse()?;
+                    input.parse::<Token![=>]>()?;
+                    Ok((key, input.parse()?))
+                })?);
+            }
+            ("traits", Meta::NameValue(nv)) => {
+                self.traits = Some(lit_str(&nv.lit)?.parse_with(elem_types)?);
+            }
+            ("fn", Meta::NameValue(nv)) => {
+                self.fn_ctor = Some(lit_str(&nv.lit)?.parse_with(elem_types)?);
+            }
+            ("assert_eq", Meta::NameValue(nv)) => {
+                self.assert_eq = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("to_lit_string", Meta::NameValue(nv)) => {
+                self.to_lit_string = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("columns", Meta::NameValue(nv)) => {
+                let columns =
+                    lit_str(&nv.lit)?.parse_with(Punctuated::<Ident, Token![,]>::parse_terminated)?;
+                self.columns.extend(columns);
+            }
+            ("bound", Meta::NameValue(nv)) => {
+                let bounds = lit_str(&nv.lit)?
+                    .parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+                self.bounds.extend(bounds);
+            }
+            ("use", Meta::NameValue(nv)) => {
+                self.uses.push(lit_str(&nv.lit)?.parse()?);
+            }
+            ("json_value", Meta::NameValue(nv)) => {
+                self.json_value = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("capacity", Meta::NameValue(nv)) => {
+                self.capacity = Some(lit_int(&nv.lit)?.clone());
+            }
+            ("version", Meta::NameValue(nv)) => {
+                self.version = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("hasher", Meta::NameValue(nv)) => {
+                self.hasher = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("range", Meta::NameValue(nv)) => {
+                let range: ExprRange = lit_str(&nv.lit)?.parse()?;
+                for bound in range.from.iter().chain(&range.to) {
+                    if check::int_value(bound).is_none() {
+                        return Err(syn::Error::new_spanned(
+                            bound,
+                            "expected an integer literal",
+                        ));
+                    }
+                }
+                self.range = Some(range);
+            }
+            ("validate", Meta::NameValue(nv)) => {
+                self.validate = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("track_caller", Meta::NameValue(nv)) => {
+                self.track_caller = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("csv", Meta::NameValue(nv)) => {
+                self.csv = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("try_method", Meta::NameValue(nv)) => {
+                self.try_method = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("fallback", Meta::NameValue(nv)) => {
+                self.fallback = Some(lit_str(&nv.lit)?.parse()?);
+            }
+            ("section", Meta::List(list)) if list.nested.len() == 1 => match &list.nested[0] {
+                NestedMeta::Meta(Meta::NameValue(nv)) => {
+                    let method = lit_str(&nv.lit)?.parse()?;
+                    self.sections.push((ident(&nv.path)?, method));
+                }
+                nested => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "expected `label = \"method\"`",
+                    ))
+                }
+            },
+            ("default", Meta::Path(path)) => match self.default_ctor {
+                None => self.default_ctor = Some(ident(path)?),
+                Some(_) => {
+                    return Err(syn::Error::new_spanned(
+                        path,
+                        "`default` can't be combined with another default constructor",
+                    ))
+                }
+            },
+            ("setter", Meta::List(list)) if list.nested.len() == 1 => match &list.nested[0] {
+                NestedMeta::Meta(Meta::NameValue(nv)) => {
+                    let method = lit_str(&nv.lit)?.parse()?;
+                    self.setters.push((ident(&nv.path)?, method));
+                }
+                nested => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "expected `name = \"method\"`",
+                    ))
+                }
+            },
+            ("ctor", Meta::List(list)) if list.nested.len() == 1 => match &list.nested[0] {
+                NestedMeta::Meta(Meta::Path(path)) if self.default_ctor.is_none() => {
+                    self.default_ctor = Some(ident(path)?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) => {
+                    let keyword = lit_str(&nv.lit)?.parse()?;
+                    self.ctors.push((ident(&nv.path)?, keyword));
+                }
+                nested => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "expected `method` or `method = \"keyword\"`, with at most one default constructor",
+                    ))
+                }
+            },
+            ("weight_sum", Meta::NameValue(nv)) => match &nv.lit {
+                Lit::Int(_) | Lit::Float(_) => self.weight_sum = Some(nv.lit.clone()),
+                lit => return Err(syn::Error::new_spanned(lit, "expected a number")),
+            },
+            (_, Meta::Path(path)) => match path.get_ident().map(Kind::from_derive) {
+                Some(Ok(kind)) => self.kind = Some(kind),
+                _ => return Err(syn::Error::new_spanned(meta, "unknown `lit` attribute")),
+            },
+            _ => return Err(syn::Error::new_spanned(meta, "unknown `lit` attribute")),
+        }
+        Ok(())
+    }
+}
+
+/// Parses the element type `T`, or the key and value types `K => V`
+fn elem_types(input: ParseStream) -> Result<(Type, Option<Type>)> {
+    let key = input.parse()?;
+    let val = match input.parse::<Option<Token![=>]>>()? {
+        Some(_) => Some(input.parse()?),
+        None => None,
+    };
+    Ok((key, val))
+}
+
+fn lit_str(lit: &Lit) -> Result<&LitStr> {
+    match lit {
+        Lit::Str(s) => Ok(s),
+        lit => Err(syn::Error::new_spanned(lit, "expected a string literal")),
+    }
+}
+
+fn ident(path: &Path) -> Result<Ident> {
+    path.get_ident()
+        .cloned()
+        .ok_or_else(|| syn::Error::new_spanned(path, "expected an identifier"))
+}
+
+fn lit_int(lit: &Lit) -> Result<&LitInt> {
+    match lit {
+        Lit::Int(i) => Ok(i),
+        lit => Err(syn::Error::new_spanned(lit, "expected an integer literal")),
+    }
+}
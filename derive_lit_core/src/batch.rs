@@ -0,0 +1,43 @@
+use proc_macro2::TokenStream;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Generics, Ident, Result, Token};
+
+use crate::attr::Options;
+use crate::expand::{self, Kind};
+
+/// The input to `derive_lits!`, e.g. `VecLit for Foo, Bar; MapLit for Baz;`
+pub struct Batch {
+    groups: Vec<(Kind, Vec<Ident>)>,
+}
+
+impl Parse for Batch {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut groups = vec![];
+
+        while !input.is_empty() {
+            let kind = Kind::from_derive(&input.parse()?)?;
+            input.parse::<Token![for]>()?;
+            let names = Punctuated::<Ident, Token![,]>::parse_separated_nonempty(input)?;
+            groups.push((kind, names.into_iter().collect()));
+
+            if !input.is_empty() {
+                input.parse::<Token![;]>()?;
+            }
+        }
+
+        Ok(Batch { groups })
+    }
+}
+
+impl Batch {
+    pub fn expand(&self) -> TokenStream {
+        let options = Options::default();
+
+        self.groups
+            .iter()
+            .flat_map(|(kind, names)| names.iter().map(move |name| (kind, name)))
+            .map(|(kind, name)| expand::generate(name, &Generics::default(), *kind, &options))
+            .collect()
+    }
+}
@@ -0,0 +1,112 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::discouraged::Speculative;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, parenthesized, Block, Expr, Result, Token};
+
+/// The input to `__binary_tree!`, e.g. `{ MyTree::new() } { MyTree::node } (1, 2, (_, 3, _))`
+pub struct BinaryTree {
+    new: Block,
+    node: TokenStream,
+    root: Subtree,
+}
+
+/// A subtree of a binary tree literal
+enum Subtree {
+    /// `_`, the empty tree
+    Empty,
+    /// `(left, value, right)` or `value { left, right }`, a node and its subtrees
+    Node(Box<Subtree>, Box<Expr>, Box<Subtree>),
+    /// `value`, a node without subtrees
+    Leaf(Box<Expr>),
+}
+
+impl Parse for BinaryTree {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let new = input.parse()?;
+        let node;
+        braced!(node in input);
+        let node = node.parse()?;
+        // an empty literal is the empty tree, as `_` is
+        let root = if input.is_empty() {
+            Subtree::Empty
+        } else {
+            parse_node(input)?
+        };
+        if !input.is_empty() {
+            return Err(input.error("expected one tree"));
+        }
+
+        Ok(BinaryTree { new, node, root })
+    }
+}
+
+/// Parses a subtree, where parentheses around exactly three parts always make a node
+fn parse_node(input: ParseStream) -> Result<Subtree> {
+    if input.peek(Token![_]) {
+        input.parse::<Token![_]>()?;
+        return Ok(Subtree::Empty);
+    }
+    if input.peek(syn::token::Paren) {
+        let fork = input.fork();
+        if let Ok(node) = parse_tuple(&fork) {
+            input.advance_to(&fork);
+            return Ok(node);
+        }
+    }
+
+    // the braces after a value are its subtrees rather than a struct literal, which can be written in parentheses
+    let value = Box::new(Expr::parse_without_eager_brace(input)?);
+    if input.peek(syn::token::Brace) {
+        let children;
+        braced!(children in input);
+        let left = parse_node(&children)?;
+        children.parse::<Token![,]>()?;
+        let right = parse_node(&children)?;
+        children.parse::<Option<Token![,]>>()?;
+        if !children.is_empty() {
+            return Err(children.error("expected the left and right subtrees"));
+        }
+        return Ok(Subtree::Node(Box::new(left), value, Box::new(right)));
+    }
+    Ok(Subtree::Leaf(value))
+}
+
+/// Parses `(left, value, right)`
+fn parse_tuple(input: ParseStream) -> Result<Subtree> {
+    let tuple;
+    parenthesized!(tuple in input);
+    let left = parse_node(&tuple)?;
+    tuple.parse::<Token![,]>()?;
+    let value = tuple.parse()?;
+    tuple.parse::<Token![,]>()?;
+    let right = parse_node(&tuple)?;
+    tuple.parse::<Option<Token![,]>>()?;
+    if !tuple.is_empty() {
+        return Err(tuple.error("expected `(left, value, right)`"));
+    }
+    Ok(Subtree::Node(
+        Box::new(left),
+        Box::new(value),
+        Box::new(right),
+    ))
+}
+
+impl BinaryTree {
+    pub fn expand(&self) -> TokenStream {
+        self.subtree(&self.root)
+    }
+
+    /// The nested constructor calls building `node`, whose subtrees are built before it, left to right
+    fn subtree(&self, node: &Subtree) -> TokenStream {
+        let (new, ctor) = (&self.new, &self.node);
+        match node {
+            Subtree::Empty => quote!(#new),
+            Subtree::Node(left, value, right) => {
+                let (left, right) = (self.subtree(left), self.subtree(right));
+                quote!(#ctor(#left, #value, #right))
+            }
+            Subtree::Leaf(value) => quote!(#ctor(#new, #value, #new)),
+        }
+    }
+}
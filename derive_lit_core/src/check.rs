@@ -0,0 +1,246 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, ExprLit, ExprRange, Lit, RangeLimits, Result, Token, UnOp};
+
+/// The buckets of a histogram literal, e.g. `0..10, 10..20`
+pub struct Buckets(Vec<ExprRange>);
+
+impl Parse for Buckets {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Punctuated::<Expr, Token![,]>::parse_terminated(input)?
+            .into_iter()
+            .map(|expr| match expr {
+                Expr::Range(range) => Ok(range),
+                expr => Err(syn::Error::new_spanned(expr, "expected a bucket range")),
+            })
+            .collect::<Result<_>>()
+            .map(Buckets)
+    }
+}
+
+impl Buckets {
+    /// Checks that the buckets are contiguous and non-overlapping, in the order they're written
+    pub fn check(&self) -> TokenStream {
+        let mut errors: Option<syn::Error> = None;
+        let mut push_error = |err: syn::Error| match &mut errors {
+            Some(errors) => errors.combine(err),
+            None => errors = Some(err),
+        };
+
+        let mut prev_hi = None;
+        for range in &self.0 {
+            let (span, lo, hi) = match (&range.from, &range.to, &range.limits) {
+                (Some(lo), Some(hi), RangeLimits::HalfOpen(_)) => match (value(lo), value(hi)) {
+                    (Ok(v_lo), Ok(v_hi)) => {
+                        let (lo, hi) = (ungroup(lo), ungroup(hi));
+                        (quote!(#lo..#hi), v_lo, v_hi)
+                    }
+                    (Err(err), _) | (_, Err(err)) => {
+                        push_error(err);
+                        continue;
+                    }
+                },
+                _ => {
+                    push_error(syn::Error::new_spanned(
+                        range,
+                        "expected a bucket range `lo..hi`",
+                    ));
+                    continue;
+                }
+            };
+
+            if lo >= hi {
+                push_error(syn::Error::new_spanned(&span, "bucket range is empty"));
+            }
+            match prev_hi {
+                Some(prev_hi) if lo > prev_hi => push_error(syn::Error::new_spanned(
+                    &span,
+                    "bucket leaves a gap after the previous bucket",
+                )),
+                Some(prev_hi) if lo < prev_hi => push_error(syn::Error::new_spanned(
+                    &span,
+                    "bucket overlaps the previous bucket",
+                )),
+                _ => {}
+            }
+            prev_hi = Some(hi);
+        }
+
+        errors.map(|err| err.to_compile_error()).unwrap_or_default()
+    }
+}
+
+/// Strips the invisible groups `macro_rules!` wraps around forwarded fragments, so errors point at the user's tokens
+pub(crate) fn ungroup(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Group(group) => ungroup(&group.expr),
+        expr => expr,
+    }
+}
+
+/// Evaluates a numeric literal, possibly negated
+pub(crate) fn value(expr: &Expr) -> Result<f64> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(i) => i.base10_parse::<i128>().map(|i| i as f64),
+            Lit::Float(f) => f.base10_parse(),
+            lit => Err(syn::Error::new_spanned(lit, "expected a numeric literal")),
+        },
+        // bounds passed through `macro_rules!` arrive wrapped in invisible groups
+        Expr::Group(group) => value(&group.expr),
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => value(&unary.expr).map(|v| -v),
+        Expr::Paren(paren) => value(&paren.expr),
+        expr => Err(syn::Error::new_spanned(expr, "expected a numeric literal")),
+    }
+}
+
+/// A literal that elements or keys can be sorted by at compile time
+#[derive(PartialEq, PartialOrd)]
+enum Sortable {
+    Int(i128),
+    Float(f64),
+    Str(String),
+    ByteStr(Vec<u8>),
+    Char(char),
+    Byte(u8),
+}
+
+/// The literal `expr` is written as, possibly negated if it's a number
+fn sortable(expr: &Expr) -> Option<Sortable> {
+    match ungroup(expr) {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(i) => i.base10_parse().ok().map(Sortable::Int),
+            Lit::Float(f) => f.base10_parse().ok().map(Sortable::Float),
+            Lit::Str(s) => Some(Sortable::Str(s.value())),
+            Lit::ByteStr(s) => Some(Sortable::ByteStr(s.value())),
+            Lit::Char(c) => Some(Sortable::Char(c.value())),
+            Lit::Byte(b) => Some(Sortable::Byte(b.value())),
+            _ => None,
+        },
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => match sortable(&unary.expr)? {
+            Sortable::Int(i) => Some(Sortable::Int(-i)),
+            Sortable::Float(f) => Some(Sortable::Float(-f)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The value of an integer literal, possibly negated
+pub(crate) fn int_value(expr: &Expr) -> Option<i128> {
+    match sortable(expr)? {
+        Sortable::Int(i) => Some(i),
+        _ => None,
+    }
+}
+
+/// Checks that `expr` is within `range` if it's an integer literal, whose bounds are integer literals too
+pub(crate) fn check_range(range: &ExprRange, expr: &Expr) -> Result<()> {
+    let i = match int_value(expr) {
+        Some(i) => i,
+        None => return Ok(()),
+    };
+    let bound = |bound: &Option<Box<Expr>>| bound.as_deref().and_then(int_value);
+    let (lo, hi) = (bound(&range.from), bound(&range.to));
+    let below = matches!(lo, Some(lo) if i < lo);
+    let above = match (hi, &range.limits) {
+        (Some(hi), RangeLimits::HalfOpen(_)) => i >= hi,
+        (Some(hi), RangeLimits::Closed(_)) => i > hi,
+        (None, _) => false,
+    };
+    if below || above {
+        let expr = ungroup(expr);
+        return Err(syn::Error::new_spanned(
+            expr,
+            format!(
+                "`{}` is outside of the range `{}`",
+                quote!(#expr).to_string().replace(' ', ""),
+                quote!(#range).to_string().replace(' ', "")
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `expr` is a literal, possibly negated if it's a number
+pub(crate) fn is_literal(expr: &Expr) -> bool {
+    match ungroup(expr) {
+        Expr::Lit(_) => true,
+        Expr::Unary(unary) if matches!(unary.op, UnOp::Neg(_)) => matches!(
+            ungroup(&unary.expr),
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(_) | Lit::Float(_),
+                ..
+            })
+        ),
+        _ => false,
+    }
+}
+
+/// Sorts `items` by the literals that `expr` gives for them, numerically or lexicographically, if they're all literals
+/// of the same kind, and otherwise leaves them in the order they're written
+pub(crate) fn sort_literals<T>(items: &mut Vec<T>, expr: impl Fn(&T) -> Option<&Expr>) {
+    let keys: Option<Vec<_>> = items
+        .iter()
+        .map(|item| expr(item).and_then(sortable))
+        .collect();
+    let keys = match keys {
+        Some(keys) => keys,
+        None => return,
+    };
+    let same_kind = keys
+        .windows(2)
+        .all(|pair| std::mem::discriminant(&pair[0]) == std::mem::discriminant(&pair[1]));
+    if !same_kind {
+        return;
+    }
+
+    let mut keyed: Vec<_> = keys.into_iter().zip(items.drain(..)).collect();
+    keyed.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    items.extend(keyed.into_iter().map(|(_, item)| item));
+}
+
+/// Sorts `items` by the literals that `expr` gives for them into strictly ascending order, for constructors that need
+/// sorted input
+///
+/// Unlike with `sort_literals`, items that can't be sorted or that are equal are errors, described as `what`.
+pub(crate) fn sort_distinct<T>(
+    items: Vec<T>,
+    expr: impl Fn(&T) -> &Expr,
+    what: &str,
+) -> Result<Vec<T>> {
+    let mut keyed = vec![];
+    for item in items {
+        let key = sortable(expr(&item)).ok_or_else(|| {
+            syn::Error::new_spanned(
+                ungroup(expr(&item)),
+                format!(
+                    "expected a number, string, char or byte literal as the {}",
+                    what
+                ),
+            )
+        })?;
+        if let Some((first, _)) = keyed.first() {
+            if std::mem::discriminant(first) != std::mem::discriminant(&key) {
+                return Err(syn::Error::new_spanned(
+                    ungroup(expr(&item)),
+                    format!("expected a literal of the same kind as the first {}", what),
+                ));
+            }
+        }
+        keyed.push((key, item));
+    }
+
+    // a stable sort, so that the second of two equal items is the one reported
+    keyed.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    if let Some(pair) = keyed.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        let dup = ungroup(expr(&pair[1].1));
+        return Err(syn::Error::new_spanned(
+            dup,
+            format!("duplicate {} `{}`", what, quote!(#dup)),
+        ));
+    }
+    Ok(keyed.into_iter().map(|(_, item)| item).collect())
+}
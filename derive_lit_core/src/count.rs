@@ -0,0 +1,47 @@
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::{Attribute, Expr, Result, Token};
+
+/// The input to `__count!`, the elements of a literal, e.g. `0, #[cfg(feature = "extra")] 9, 8`
+///
+/// Only the attributes of each element are kept, so that the count leaves out the elements that `#[cfg]` removes.
+pub struct Count(Vec<Vec<Attribute>>);
+
+impl Parse for Count {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut elems = vec![];
+        while !input.is_empty() {
+            let attrs = match input.fork().parse::<TokenTree>()? {
+                // an `expr` fragment forwarded by `macro_rules!` is in an invisible group, with its attributes inside
+                TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+                    input.parse::<TokenTree>()?;
+                    Parser::parse2(elem_attrs, group.stream())?
+                }
+                _ => elem_attrs(input)?,
+            };
+            elems.push(attrs);
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Count(elems))
+    }
+}
+
+/// Parses an element, giving its attributes
+fn elem_attrs(input: ParseStream) -> Result<Vec<Attribute>> {
+    let attrs = input.call(Attribute::parse_outer)?;
+    input.parse::<Expr>()?;
+    Ok(attrs)
+}
+
+impl Count {
+    /// The number of elements, as a constant expression that doesn't evaluate them
+    pub fn expand(&self) -> TokenStream {
+        let elems = self.0.iter().map(|attrs| quote!(#(#attrs)* ()));
+        quote!(<[()]>::len(&[#(#elems),*]))
+    }
+}
@@ -0,0 +1,114 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Block, Ident, LitStr, Result};
+
+/// The input to `__csv!`, e.g. `{ MyTable::new() } push_row "id,name\n1,a\n2,b"`
+pub struct Csv {
+    new: Block,
+    method: Ident,
+    csv: LitStr,
+}
+
+impl Parse for Csv {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Csv {
+            new: input.parse()?,
+            method: input.parse()?,
+            csv: input.parse()?,
+        })
+    }
+}
+
+impl Csv {
+    /// Parses the CSV, and adds each record after the header as an array of its fields
+    pub fn expand(&self) -> TokenStream {
+        let records = match parse_csv(&self.csv.value()) {
+            Ok(records) => records,
+            Err(reason) => {
+                return syn::Error::new(self.csv.span(), format!("invalid CSV: {}", reason))
+                    .to_compile_error()
+            }
+        };
+
+        let new = &self.new;
+        let method = &self.method;
+        let temp = Ident::new("temp", Span::mixed_site());
+        let rows = records.iter().skip(1).map(|record| {
+            let fields = record
+                .iter()
+                .map(|field| LitStr::new(field, self.csv.span()));
+            quote!(#temp.#method([#(#fields),*]);)
+        });
+
+        quote! {
+            {
+                let mut #temp = #new;
+                #( #rows )*
+                #temp
+            }
+        }
+    }
+}
+
+/// Parses the records of a CSV, which all have as many fields as the header and may be quoted, with `""` for a quote
+///
+/// Blank lines are skipped, so the CSV can start and end with a newline.
+fn parse_csv(csv: &str) -> std::result::Result<Vec<Vec<String>>, String> {
+    let mut records: Vec<Vec<String>> = vec![];
+    let mut chars = csv.chars().peekable();
+    let mut line = 1;
+    while chars.peek().is_some() {
+        let mut record = vec![];
+        let mut field = String::new();
+        let mut quoted = false;
+        loop {
+            match chars.next() {
+                Some('"') if quoted && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                Some('"') if quoted => {
+                    quoted = false;
+                    if !matches!(chars.peek(), None | Some(',' | '\n' | '\r')) {
+                        return Err(format!(
+                            "unexpected text after a quoted field on line {}",
+                            line
+                        ));
+                    }
+                }
+                Some('"') if field.is_empty() => quoted = true,
+                Some('\n') if quoted => {
+                    line += 1;
+                    field.push('\n');
+                }
+                Some(c) if quoted => field.push(c),
+                None if quoted => return Err(format!("unclosed quote on line {}", line)),
+                Some(',') => record.push(std::mem::take(&mut field)),
+                Some('\r') if chars.peek() == Some(&'\n') => {}
+                Some('\n') | None => break,
+                Some(c) => field.push(c),
+            }
+        }
+        record.push(field);
+        // a blank line is a single empty field
+        if record.len() > 1 || !record[0].is_empty() {
+            if let Some(header) = records.first() {
+                if record.len() != header.len() {
+                    return Err(format!(
+                        "line {} has {} fields, but the header has {}",
+                        line,
+                        record.len(),
+                        header.len()
+                    ));
+                }
+            }
+            records.push(record);
+        }
+        line += 1;
+    }
+    if records.is_empty() {
+        return Err("expected a header".to_string());
+    }
+    Ok(records)
+}
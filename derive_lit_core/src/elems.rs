@@ -0,0 +1,495 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{
+    bracketed, parenthesized, Attribute, Block, Expr, ExprLit, ExprRange, Ident, Lit, LitStr,
+    Local, Path, Result, Stmt, Token, Type,
+};
+
+use crate::{check, expand};
+
+mod kw {
+    syn::custom_keyword!(include);
+}
+
+/// The input to `__elems!`, e.g. `{ MyStruct::new() } push [skip_none strict sort reverse split_lines splat = chars track_caller validate = is_valid range = (0..=255) non_zero = (NonZeroU32) setter(growth = set_growth)]; growth: 2; 0, in 1..10, include "words.txt", let n = 2; n`, with the flags in `[...]` being optional
+///
+/// This is the extended element syntax that plain `expr` fragments can't express.
+pub struct Elems {
+    new: Block,
+    method: Ident,
+    /// Whether elements are `Option`s that are only added when `Some`
+    skip_none: bool,
+    /// Whether adding an element the collection already contains panics
+    strict: bool,
+    /// The macro that elements are passed to as tokens, instead of being parsed as expressions
+    tokens: Option<Path>,
+    /// Whether elements are wrapped in `Cow`, borrowing string literals and owning the others
+    cow: bool,
+    /// Whether byte string elements add each of their bytes
+    splat: bool,
+    /// The method, `chars` or `bytes`, that string literal elements are split with
+    splat_str: Option<Ident>,
+    /// Whether the method is also given the `Location` of the literal
+    track_caller: bool,
+    /// Whether elements are added in the reverse of the order they're written, including the items of each element
+    reverse: bool,
+    /// The macro that each added element is logged with
+    trace: Option<Path>,
+    /// The `const fn` that literal elements are checked with
+    validate: Option<Path>,
+    /// The `NonZero` type that integer literal elements are converted to
+    non_zero: Option<Type>,
+    /// The values of the `name: val` pairs before the elements, with the setters they're passed to
+    setters: Vec<(Ident, Expr)>,
+    elems: Vec<Elem>,
+    /// The files read by `include` elements, which are also included into the expansion so that changing them rebuilds it
+    includes: Vec<String>,
+    /// The errors of elements outside of the `range`, which are expanded inside the literal's block since there can be
+    /// several of them
+    errors: TokenStream,
+}
+
+/// An element of a literal
+enum Elem {
+    /// A plain element, possibly with `#[cfg]` attributes
+    Expr(Expr),
+    /// `in iter`, adding every item of `iter`
+    Iter(Expr),
+    /// The tokens of an element, in `#[lit(tokens = "...")]` mode
+    Tokens(TokenStream),
+    /// `let pat = val;`, a binding that the elements after it can use
+    Let(Box<Local>),
+}
+
+impl Parse for Elems {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let new = input.parse()?;
+        let method = input.parse()?;
+
+        let mut skip_none = false;
+        let mut strict = false;
+        let mut sort = false;
+        let mut reverse = false;
+        let mut cow = false;
+        let mut split_lines = false;
+        let mut splat = false;
+        let mut splat_str = None;
+        let mut track_caller = false;
+        let mut tokens = None;
+        let mut trace = None;
+        let mut validate = None;
+        let mut range = None;
+        let mut non_zero = None;
+        let mut setter_methods = vec![];
+        if input.peek(syn::token::Bracket) {
+            let flags;
+            bracketed!(flags in input);
+            while !flags.is_empty() {
+                let flag: Ident = flags.parse()?;
+                match flag.to_string().as_str() {
+                    "skip_none" => skip_none = true,
+                    "strict" => strict = true,
+                    "sort" => sort = true,
+                    "reverse" => reverse = true,
+                    "cow" => cow = true,
+                    "split_lines" => split_lines = true,
+                    "splat" => {
+                        splat = true;
+                        if flags.parse::<Option<Token![=]>>()?.is_some() {
+                            splat_str = Some(flags.parse()?);
+                        }
+                    }
+                    "track_caller" => track_caller = true,
+                    "tokens" => {
+                        flags.parse::<Token![=]>()?;
+                        tokens = Some(flags.parse()?);
+                    }
+                    "trace" => {
+                        flags.parse::<Token![=]>()?;
+                        trace = Some(flags.parse()?);
+                    }
+                    "validate" => {
+                        flags.parse::<Token![=]>()?;
+                        validate = Some(flags.parse()?);
+                    }
+                    "range" => {
+                        flags.parse::<Token![=]>()?;
+                        let bounds;
+                        parenthesized!(bounds in flags);
+                        range = Some(bounds.parse::<ExprRange>()?);
+                    }
+                    "non_zero" => {
+                        flags.parse::<Token![=]>()?;
+                        let ty;
+                        parenthesized!(ty in flags);
+                        non_zero = Some(ty.parse()?);
+                    }
+                    "setter" => {
+                        let setter;
+                        parenthesized!(setter in flags);
+                        let name: Ident = setter.parse()?;
+                        setter.parse::<Token![=]>()?;
+                        setter_methods.push((name, setter.parse::<Ident>()?));
+                    }
+                    _ => return Err(syn::Error::new(flag.span(), "unknown flag")),
+                }
+            }
+        }
+        input.parse::<Token![;]>()?;
+
+        let mut setters = vec![];
+        if !setter_methods.is_empty()
+            && input.peek(Ident)
+            && input.peek2(Token![:])
+            && !input.peek2(Token![::])
+        {
+            loop {
+                let name: Ident = input.parse()?;
+                let method = match setter_methods.iter().find(|(setter, _)| *setter == name) {
+                    Some((_, method)) => method.clone(),
+                    None => {
+                        let names: Vec<_> = setter_methods
+                            .iter()
+                            .map(|(setter, _)| format!("`{}`", setter))
+                            .collect();
+                        return Err(syn::Error::new(
+                            name.span(),
+                            format!("unknown setter, expected {}", names.join(", ")),
+                        ));
+                    }
+                };
+                input.parse::<Token![:]>()?;
+                setters.push((method, input.parse()?));
+                if input.parse::<Option<Token![;]>>()?.is_some() {
+                    break;
+                }
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        let mut elems = vec![];
+        let mut includes = vec![];
+        while !input.is_empty() {
+            // a binding ends with its own `;`, instead of a `,` like elements
+            if input.peek(Token![let]) {
+                match input.parse()? {
+                    Stmt::Local(local) => elems.push(Elem::Let(Box::new(local))),
+                    stmt => return Err(syn::Error::new_spanned(stmt, "expected a `let` binding")),
+                }
+                continue;
+            }
+            if tokens.is_some() {
+                let mut elem = TokenStream::new();
+                while !input.is_empty() && !input.peek(Token![,]) {
+                    elem.extend(Some(input.parse::<TokenTree>()?));
+                }
+                elems.push(Elem::Tokens(elem));
+            } else if input.peek(kw::include) && input.peek2(LitStr) {
+                input.parse::<kw::include>()?;
+                let (path, lines) = read_lines(&input.parse()?)?;
+                elems.extend(lines.into_iter().map(|line| {
+                    Elem::Expr(Expr::Lit(ExprLit {
+                        attrs: vec![],
+                        lit: Lit::Str(line),
+                    }))
+                }));
+                includes.push(path);
+            } else if input.parse::<Option<Token![in]>>()?.is_some() {
+                elems.push(Elem::Iter(input.parse()?));
+            } else {
+                elems.push(Elem::Expr(input.parse()?));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        // every element out of the range is reported, not just the first
+        let mut errors = TokenStream::new();
+        if let Some(range) = &range {
+            for elem in &elems {
+                if let Elem::Expr(expr) = elem {
+                    if let Err(err) = check::check_range(range, expr) {
+                        errors.extend(err.to_compile_error());
+                    }
+                }
+            }
+        }
+
+        // each line keeps its `\n`, so the chunks still add up to the whole literal
+        if split_lines {
+            elems = elems
+                .into_iter()
+                .flat_map(|elem| match elem {
+                    Elem::Expr(Expr::Lit(ExprLit {
+                        attrs,
+                        lit: Lit::Str(lit),
+                    })) if lit.value().contains('\n') => lit
+                        .value()
+                        .split_inclusive('\n')
+                        .map(|line| {
+                            Elem::Expr(Expr::Lit(ExprLit {
+                                attrs: attrs.clone(),
+                                lit: Lit::Str(LitStr::new(line, lit.span())),
+                            }))
+                        })
+                        .collect(),
+                    elem => vec![elem],
+                })
+                .collect();
+        }
+        if sort {
+            check::sort_literals(&mut elems, |elem| match elem {
+                Elem::Expr(expr) => Some(expr),
+                _ => None,
+            });
+        }
+        // a binding comes before the elements using it, which reversing would undo
+        if reverse {
+            if let Some(Elem::Let(local)) = elems.iter().find(|elem| matches!(elem, Elem::Let(_))) {
+                return Err(syn::Error::new(
+                    local.let_token.span,
+                    "a `let` binding can't be used in a literal with `#[lit(reverse)]`",
+                ));
+            }
+            elems.reverse();
+        }
+
+        Ok(Elems {
+            new,
+            method,
+            skip_none,
+            strict,
+            cow,
+            splat,
+            splat_str,
+            track_caller,
+            reverse,
+            tokens,
+            trace,
+            validate,
+            non_zero,
+            setters,
+            elems,
+            includes,
+            errors,
+        })
+    }
+}
+
+/// The `const` item asserting that `validate` accepts the literal `expr`, reported at the literal
+fn validate_item(validate: &Path, expr: &Expr) -> TokenStream {
+    // the `#[cfg]` of the element goes on the item, since attributes on expressions are unstable
+    let (attrs, expr) = split_attrs(expr);
+    let message = format!("invalid element `{}` in literal", quote!(#expr));
+    quote_spanned! {expr.span()=>
+        #(#attrs)*
+        const _: () = ::std::assert!(#validate(#expr), "{}", #message);
+    }
+}
+
+/// The attributes of the literal `expr`, possibly negated, and the literal without them
+fn split_attrs(expr: &Expr) -> (Vec<Attribute>, Expr) {
+    let mut expr = check::ungroup(expr).clone();
+    let attrs = match &mut expr {
+        Expr::Lit(lit) => std::mem::take(&mut lit.attrs),
+        Expr::Unary(unary) => std::mem::take(&mut unary.attrs),
+        _ => vec![],
+    };
+    (attrs, expr)
+}
+
+/// The integer literal `expr` as the `NonZero` type `ty`, converted in a `const` so that a literal the type can't hold
+/// is a compile error, with the `#[cfg]` of the element kept on it
+fn non_zero_expr(ty: &Type, expr: &Expr) -> TokenStream {
+    let (attrs, expr) = split_attrs(expr);
+    quote_spanned! {expr.span()=>
+        #(#attrs)*
+        {
+            const ELEM: #ty = match <#ty>::new(#expr) {
+                ::std::option::Option::Some(elem) => elem,
+                ::std::option::Option::None => ::std::panic!("zero element in literal"),
+            };
+            ELEM
+        }
+    }
+}
+
+/// Reads the non-blank lines of the file at `path` as string literals, along with the full path of the file
+fn read_lines(path: &LitStr) -> Result<(String, Vec<LitStr>)> {
+    let (full, contents) = read_file(path)?;
+    let lines = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| LitStr::new(line, path.span()))
+        .collect();
+    Ok((full, lines))
+}
+
+/// Reads the file at `path`, relative to the directory of the crate's manifest, along with its full path
+pub(crate) fn read_file(path: &LitStr) -> Result<(String, String)> {
+    let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full = std::path::Path::new(&dir).join(path.value());
+    let contents = std::fs::read_to_string(&full).map_err(|err| {
+        syn::Error::new(
+            path.span(),
+            format!("can't read `{}`: {}", full.display(), err),
+        )
+    })?;
+    Ok((full.display().to_string(), contents))
+}
+
+impl Elems {
+    pub fn expand(&self) -> TokenStream {
+        let new = &self.new;
+        let method = &self.method;
+        let temp = Ident::new("temp", Span::mixed_site());
+        let origin = if self.track_caller {
+            quote!(, ::std::panic::Location::caller())
+        } else {
+            quote!()
+        };
+
+        // `what` describes the element in the panic of a `strict` literal, and `span` is where a
+        // mistyped element is reported
+        let add = |what: String, span: Span| {
+            let elem = Ident::new("elem", Span::mixed_site().located_at(span));
+            let trace = expand::trace_stmt(self.trace.as_ref(), "{:?}", quote!(&#elem));
+            let insert = if self.strict {
+                quote! {
+                    #trace
+                    if !#temp.#method(#elem #origin) {
+                        panic!("duplicate element {} in literal", #what);
+                    }
+                }
+            } else {
+                quote! {
+                    #trace
+                    #temp.#method(#elem #origin);
+                }
+            };
+            let add = if self.skip_none {
+                quote! {
+                    if let Some(#elem) = #elem {
+                        #insert
+                    }
+                }
+            } else {
+                insert
+            };
+            (elem, add)
+        };
+
+        let stmts = self.elems.iter().map(|e| match e {
+            // the `#[cfg]` of a splatted byte string or string goes on the loop over its parts
+            Elem::Expr(Expr::Lit(ExprLit {
+                attrs,
+                lit: lit @ Lit::ByteStr(_),
+            })) if self.splat => {
+                let (elem, add) = add(format!("of `{}`", quote!(#lit)), lit.span());
+                let iter = if self.reverse {
+                    quote!(::std::iter::Iterator::rev(::std::iter::IntoIterator::into_iter(*#lit)))
+                } else {
+                    quote!(*#lit)
+                };
+                quote! {
+                    #(#attrs)*
+                    for #elem in #iter {
+                        #add
+                    }
+                }
+            }
+            Elem::Expr(Expr::Lit(ExprLit {
+                attrs,
+                lit: lit @ Lit::Str(_),
+            })) if self.splat_str.is_some() => {
+                let method = &self.splat_str;
+                let (elem, add) = add(format!("of `{}`", quote!(#lit)), lit.span());
+                let iter = if self.reverse {
+                    quote!(::std::iter::Iterator::rev(#lit.#method()))
+                } else {
+                    quote!(#lit.#method())
+                };
+                quote! {
+                    #(#attrs)*
+                    for #elem in #iter {
+                        #add
+                    }
+                }
+            }
+            // as in the plain arm, the one-element array lets `#[cfg]` remove the element, and the
+            // validation of a literal is removed with it
+            Elem::Expr(expr) => {
+                let validate = match &self.validate {
+                    Some(validate) if check::is_literal(expr) => validate_item(validate, expr),
+                    _ => quote!(),
+                };
+                let (elem, add) = add(format!("`{}`", quote!(#expr)), expr.span());
+                let cow = match expr {
+                    _ if !self.cow => quote!(),
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(_), ..
+                    }) => quote!(let #elem = ::std::borrow::Cow::Borrowed(#elem);),
+                    _ => quote! {
+                        let #elem = ::std::borrow::Cow::Owned(::std::convert::Into::into(#elem));
+                    },
+                };
+                let expr = match &self.non_zero {
+                    Some(ty) if check::int_value(expr).is_some() => non_zero_expr(ty, expr),
+                    _ => quote!(#expr),
+                };
+                quote! {
+                    #validate
+                    for #elem in [#expr] {
+                        #cow
+                        #add
+                    }
+                }
+            }
+            Elem::Iter(iter) => {
+                let (elem, add) = add(format!("from `{}`", quote!(#iter)), iter.span());
+                let iter = if self.reverse {
+                    expand::reversed(quote!(#iter))
+                } else {
+                    quote!(#iter)
+                };
+                quote! {
+                    for #elem in #iter {
+                        #add
+                    }
+                }
+            }
+            Elem::Let(local) => quote!(#local),
+            Elem::Tokens(tokens) => {
+                let translate = &self.tokens;
+                let (elem, add) = add(format!("`{}`", tokens), tokens.span());
+                quote! {
+                    for #elem in [#translate!(#tokens)] {
+                        #add
+                    }
+                }
+            }
+        });
+
+        let setters = self
+            .setters
+            .iter()
+            .map(|(method, val)| quote!(#temp.#method(#val);));
+
+        let includes = &self.includes;
+        let errors = &self.errors;
+        quote! {
+            {
+                #errors
+                #( const _: &[u8] = ::std::include_bytes!(#includes); )*
+                let mut #temp = #new;
+                #( #setters )*
+                #( #stmts )*
+                #temp
+            }
+        }
+    }
+}
@@ -0,0 +1,413 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::{bracketed, Block, Expr, ExprLit, Ident, Lit, LitStr, Local, Path, Result, Stmt, Token};
+
+use crate::attr::Duplicate;
+use crate::{check, elems, expand, json};
+
+mod kw {
+    syn::custom_keyword!(include_json);
+}
+
+/// The input to `__entries!`, e.g. `{ MyMap::new() } insert [fallback = set_default]; fmt "user:{id}" => 1, ..base, include_json "config.json", let n = 2; "n" => n, _ => 0`, with the flags in `[...]` being optional
+///
+/// This is the extended entry syntax that plain `expr` fragments can't express.
+pub struct Entries {
+    new: Block,
+    method: Ident,
+    /// The method given the value of a `_ => val` entry
+    fallback: Option<Ident>,
+    on_duplicate: Duplicate,
+    /// Whether string literal keys are converted with `ToOwned`
+    own_keys: bool,
+    /// Whether keys are converted with `Into`
+    key_into: bool,
+    /// The tuple struct or function that keys are wrapped in, before any conversion with `Into`
+    key_wrap: Option<Path>,
+    /// Whether values are converted with `Into`
+    value_into: bool,
+    /// The macro that each inserted entry is logged with
+    trace: Option<Path>,
+    /// The function constructing the nested maps that dotted string literal keys are split into
+    dotted: Option<Path>,
+    entries: Vec<Entry>,
+    /// The files read by `include_json` entries, which are also included into the expansion so that changing them
+    /// rebuilds it
+    includes: Vec<String>,
+}
+
+/// An entry of a map literal
+enum Entry {
+    /// `key => val`
+    Pair(Key, Expr),
+    /// `..map`, inserting every `(key, val)` pair of `map`
+    Spread(Expr),
+    /// `key => ..iter` in a multimap literal, inserting every item of `iter` under `key`
+    Values(Key, Expr),
+    /// `let pat = val;`, a binding that the entries after it can use
+    Let(Box<Local>),
+    /// The entries whose dotted keys start with `prefix.`, by the rest of their keys, inserted into a nested map that's
+    /// inserted under `prefix`
+    Nested(LitStr, Vec<Entry>),
+}
+
+/// The key of a map literal's entry
+enum Key {
+    /// A plain key
+    Expr(Box<Expr>),
+    /// `k1, k2, ...`, for the tuple of the keys
+    Composite(Vec<Expr>),
+    /// `fmt "..."`, expanded through `format!`
+    Fmt(LitStr),
+    /// `_`, for the fallback value
+    Fallback,
+}
+
+impl Parse for Entries {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let new = input.parse()?;
+        let method = input.parse()?;
+
+        let mut fallback = None;
+        let mut on_duplicate = Duplicate::default();
+        let mut multi = false;
+        let mut composite_keys = false;
+        let mut own_keys = false;
+        let mut key_into = false;
+        let mut key_wrap = None;
+        let mut value_into = false;
+        let mut trace = None;
+        let mut dotted = None;
+        let mut json_value = None;
+        let mut sort = false;
+        if input.peek(syn::token::Bracket) {
+            let flags;
+            bracketed!(flags in input);
+            while !flags.is_empty() {
+                let flag: Ident = flags.parse()?;
+                match flag.to_string().as_str() {
+                    "fallback" => {
+                        flags.parse::<Token![=]>()?;
+                        fallback = Some(flags.parse()?);
+                    }
+                    "multi" => multi = true,
+                    "sort" => sort = true,
+                    "composite_keys" => composite_keys = true,
+                    "own_keys" => own_keys = true,
+                    "key_into" => key_into = true,
+                    "key_wrap" => {
+                        flags.parse::<Token![=]>()?;
+                        key_wrap = Some(flags.parse()?);
+                    }
+                    "value_into" => value_into = true,
+                    "trace" => {
+                        flags.parse::<Token![=]>()?;
+                        trace = Some(flags.parse()?);
+                    }
+                    "dotted" => {
+                        flags.parse::<Token![=]>()?;
+                        dotted = Some(flags.parse()?);
+                    }
+                    "json_value" => {
+                        flags.parse::<Token![=]>()?;
+                        json_value = Some(flags.parse()?);
+                    }
+                    "on_duplicate" => {
+                        flags.parse::<Token![=]>()?;
+                        on_duplicate = Duplicate::from_ident(&flags.parse()?)?;
+                    }
+                    _ => return Err(syn::Error::new(flag.span(), "unknown flag")),
+                }
+            }
+        }
+        input.parse::<Token![;]>()?;
+
+        let mut entries = vec![];
+        let mut includes = vec![];
+        while !input.is_empty() {
+            // a binding ends with its own `;`, instead of a `,` like entries
+            if input.peek(Token![let]) {
+                match input.parse()? {
+                    Stmt::Local(local) => entries.push(Entry::Let(Box::new(local))),
+                    stmt => return Err(syn::Error::new_spanned(stmt, "expected a `let` binding")),
+                }
+                continue;
+            }
+            if input.peek(kw::include_json) && input.peek2(LitStr) {
+                input.parse::<kw::include_json>()?;
+                let path = input.parse()?;
+                let (full, json) = elems::read_file(&path)?;
+                let pairs = json::entries(&json, &path, json_value.as_ref())?;
+                entries.extend(pairs.into_iter().map(|(key, val)| {
+                    let key = Expr::Lit(ExprLit {
+                        attrs: vec![],
+                        lit: Lit::Str(key),
+                    });
+                    Entry::Pair(Key::Expr(Box::new(key)), val)
+                }));
+                includes.push(full);
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+                continue;
+            }
+            if input.peek(Token![..]) && !input.peek(Token![..=]) {
+                input.parse::<Token![..]>()?;
+                entries.push(Entry::Spread(input.parse()?));
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+                continue;
+            }
+
+            let fmt = matches!(input.fork().parse::<Ident>(), Ok(ident) if ident == "fmt");
+            let key = if fmt && input.peek2(LitStr) {
+                input.parse::<Ident>()?;
+                Key::Fmt(input.parse()?)
+            } else if input.peek(Token![_]) {
+                let underscore = input.parse::<Token![_]>()?;
+                if fallback.is_none() {
+                    return Err(syn::Error::new(
+                        underscore.span,
+                        "a `_ => val` entry needs `#[lit(fallback = \"...\")]`",
+                    ));
+                }
+                Key::Fallback
+            } else {
+                let key = input.parse()?;
+                if composite_keys && input.peek(Token![,]) {
+                    let mut keys = vec![key];
+                    while input.parse::<Option<Token![,]>>()?.is_some() {
+                        keys.push(input.parse()?);
+                    }
+                    Key::Composite(keys)
+                } else {
+                    Key::Expr(Box::new(key))
+                }
+            };
+            input.parse::<Token![=>]>()?;
+            if multi
+                && !matches!(key, Key::Fallback)
+                && input.peek(Token![..])
+                && !input.peek(Token![..=])
+            {
+                input.parse::<Token![..]>()?;
+                entries.push(Entry::Values(key, input.parse()?));
+            } else {
+                entries.push(Entry::Pair(key, input.parse()?));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        // the fallback value is kept after the sorted entries
+        if sort {
+            let (mut fallbacks, mut pairs): (Vec<_>, Vec<_>) = entries
+                .into_iter()
+                .partition(|entry| matches!(entry, Entry::Pair(Key::Fallback, _)));
+            check::sort_literals(&mut pairs, |entry| match entry {
+                Entry::Pair(Key::Expr(key), _) => Some(key),
+                _ => None,
+            });
+            pairs.append(&mut fallbacks);
+            entries = pairs;
+        }
+        if dotted.is_some() {
+            entries = nest(entries)?;
+        }
+
+        Ok(Entries {
+            new,
+            method,
+            fallback,
+            on_duplicate,
+            own_keys,
+            key_into,
+            key_wrap,
+            value_into,
+            trace,
+            dotted,
+            entries,
+            includes,
+        })
+    }
+}
+
+impl Entries {
+    pub fn expand(&self) -> TokenStream {
+        let new = &self.new;
+        let temp = Ident::new("temp", Span::mixed_site());
+        let stmts = self.stmts(&self.entries, &temp);
+
+        let includes = &self.includes;
+        quote! {
+            {
+                #( const _: &[u8] = ::std::include_bytes!(#includes); )*
+                let mut #temp = #new;
+                #stmts
+                #temp
+            }
+        }
+    }
+
+    /// The statements adding `entries` to `temp`
+    fn stmts(&self, entries: &[Entry], temp: &Ident) -> TokenStream {
+        let method = &self.method;
+        let fallback = &self.fallback;
+        let insert = |key: TokenStream, val: TokenStream| {
+            expand::insert_entry(
+                &quote!(#temp),
+                &quote!(#method),
+                self.on_duplicate,
+                self.trace.as_ref(),
+                &key,
+                &val,
+            )
+        };
+        let key_expr = |key: &Key| {
+            let key = match key {
+                Key::Expr(key) => match &**key {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(_), ..
+                    }) if self.own_keys => quote!(::std::borrow::ToOwned::to_owned(#key)),
+                    key => quote!(#key),
+                },
+                Key::Composite(keys) => {
+                    // spanned at the first key, so a key type of another arity is reported at the entry
+                    quote_spanned!(keys[0].span()=> (#(#keys),*))
+                }
+                Key::Fmt(key) => quote!(::std::format!(#key)),
+                Key::Fallback => unreachable!("the fallback entry has no key"),
+            };
+            expand::key_expr(self.key_wrap.as_ref(), self.key_into, key)
+        };
+        let val_expr = |val: TokenStream| expand::into_expr(self.value_into, val);
+        let stmts = entries.iter().map(|entry| match entry {
+            Entry::Pair(Key::Fallback, val) => {
+                let val = val_expr(quote!(#val));
+                quote!(#temp.#fallback(#val);)
+            }
+            Entry::Pair(key, val) => insert(key_expr(key), val_expr(quote!(#val))),
+            Entry::Let(local) => quote!(#local),
+            Entry::Nested(prefix, entries) => {
+                let dotted = &self.dotted;
+                let stmts = self.stmts(entries, temp);
+                let key = Key::Expr(Box::new(Expr::Lit(ExprLit {
+                    attrs: vec![],
+                    lit: Lit::Str(prefix.clone()),
+                })));
+                // the nested map has its own `temp`, shadowing the outer one inside its block
+                let val = val_expr(quote! {
+                    {
+                        let mut #temp = #dotted();
+                        #stmts
+                        #temp
+                    }
+                });
+                insert(key_expr(&key), val)
+            }
+            Entry::Spread(map) => {
+                let key = Ident::new("key", Span::mixed_site());
+                let val = Ident::new("val", Span::mixed_site());
+                let insert = insert(quote!(#key), quote!(#val));
+                quote! {
+                    for (#key, #val) in #map {
+                        #insert
+                    }
+                }
+            }
+            Entry::Values(key, iter) => {
+                let key_expr = key_expr(key);
+                let key = Ident::new("key", Span::mixed_site());
+                let val = Ident::new("val", Span::mixed_site());
+                let insert = insert(
+                    quote!(::std::clone::Clone::clone(&#key)),
+                    val_expr(quote!(#val)),
+                );
+                quote! {
+                    let #key = #key_expr;
+                    for #val in #iter {
+                        #insert
+                    }
+                }
+            }
+        });
+        quote!(#( #stmts )*)
+    }
+}
+
+/// Groups the entries with dotted string literal keys, like `"server.port"`, into nested entries for each segment before
+/// the last, which are placed where their first key was written
+///
+/// An entry can't join a group across a `let` or `..map` entry, since that would move it before them.
+fn nest(entries: Vec<Entry>) -> Result<Vec<Entry>> {
+    let mut nested: Vec<Entry> = vec![];
+    for entry in entries {
+        let (prefix, rest, val) = match entry {
+            Entry::Pair(Key::Expr(key), val) => match *key {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(key), ..
+                }) if key.value().contains('.') => {
+                    let value = key.value();
+                    let (prefix, rest) = value.split_once('.').unwrap();
+                    (
+                        LitStr::new(prefix, key.span()),
+                        LitStr::new(rest, key.span()),
+                        val,
+                    )
+                }
+                key => {
+                    nested.push(Entry::Pair(Key::Expr(Box::new(key)), val));
+                    continue;
+                }
+            },
+            entry => {
+                nested.push(entry);
+                continue;
+            }
+        };
+        let rest = Entry::Pair(
+            Key::Expr(Box::new(Expr::Lit(ExprLit {
+                attrs: vec![],
+                lit: Lit::Str(rest),
+            }))),
+            val,
+        );
+        let group = nested.iter().position(|entry| match entry {
+            Entry::Nested(other, _) => other.value() == prefix.value(),
+            _ => false,
+        });
+        match group {
+            Some(i) => {
+                if nested[i + 1..]
+                    .iter()
+                    .any(|entry| matches!(entry, Entry::Let(_) | Entry::Spread(_)))
+                {
+                    return Err(syn::Error::new(
+                        prefix.span(),
+                        format!(
+                            "the entries under `{}.` can't be split by a `let` or `..` entry",
+                            prefix.value()
+                        ),
+                    ));
+                }
+                if let Entry::Nested(_, entries) = &mut nested[i] {
+                    entries.push(rest);
+                }
+            }
+            None => nested.push(Entry::Nested(prefix, vec![rest])),
+        }
+    }
+
+    nested
+        .into_iter()
+        .map(|entry| match entry {
+            Entry::Nested(prefix, entries) => Ok(Entry::Nested(prefix, nest(entries)?)),
+            entry => Ok(entry),
+        })
+        .collect()
+}
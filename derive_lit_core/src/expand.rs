@@ -0,0 +1,2781 @@
+use heck::*;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{
+    ConstParam, Data, DeriveInput, Fields, GenericParam, Generics, Ident, LitInt, LitStr, Path,
+    Result, Type, TypeParam,
+};
+
+use crate::attr::{Duplicate, Options};
+
+/// The kind of literal a derive generates a macro for
+#[derive(Clone, Copy)]
+pub enum Kind {
+    Vec,
+    VecFront,
+    Set,
+    Map,
+    SparseMatrix,
+    Histogram,
+    Sampler,
+    Cache,
+    VersionMap,
+    DirTree,
+    Channel,
+    SegmentTree,
+    UnionFind,
+    BinaryTree,
+    Rope,
+    Soa,
+    World,
+}
+
+impl Kind {
+    /// Looks up the kind generated by the derive called `name`
+    pub fn from_derive(name: &Ident) -> Result<Self> {
+        match name.to_string().as_str() {
+            "VecLit" => Ok(Kind::Vec),
+            "VecFrontLit" => Ok(Kind::VecFront),
+            "SetLit" => Ok(Kind::Set),
+            "MapLit" => Ok(Kind::Map),
+            "SparseMatrixLit" => Ok(Kind::SparseMatrix),
+            "HistogramLit" => Ok(Kind::Histogram),
+            "SamplerLit" => Ok(Kind::Sampler),
+            "CacheLit" => Ok(Kind::Cache),
+            "VersionMapLit" => Ok(Kind::VersionMap),
+            "DirTreeLit" => Ok(Kind::DirTree),
+            "ChannelLit" => Ok(Kind::Channel),
+            "SegmentTreeLit" => Ok(Kind::SegmentTree),
+            "UnionFindLit" => Ok(Kind::UnionFind),
+            "BinaryTreeLit" => Ok(Kind::BinaryTree),
+            "RopeLit" => Ok(Kind::Rope),
+            "SoaLit" => Ok(Kind::Soa),
+            "WorldLit" => Ok(Kind::World),
+            _ => Err(syn::Error::new(name.span(), "unknown derive_lit derive")),
+        }
+    }
+
+    /// Looks up the kind named by `#[lit(kind = "...")]`, like `vec` for the kind of `VecLit`
+    pub fn from_name(name: &LitStr) -> Result<Self> {
+        match name.value().as_str() {
+            "vec" => Ok(Kind::Vec),
+            "front" => Ok(Kind::VecFront),
+            "set" => Ok(Kind::Set),
+            "map" => Ok(Kind::Map),
+            "sparse_matrix" => Ok(Kind::SparseMatrix),
+            "histogram" => Ok(Kind::Histogram),
+            "sampler" => Ok(Kind::Sampler),
+            "cache" => Ok(Kind::Cache),
+            "version_map" => Ok(Kind::VersionMap),
+            "dir_tree" => Ok(Kind::DirTree),
+            "channel" => Ok(Kind::Channel),
+            "segment_tree" => Ok(Kind::SegmentTree),
+            "union_find" => Ok(Kind::UnionFind),
+            "binary_tree" => Ok(Kind::BinaryTree),
+            "rope" => Ok(Kind::Rope),
+            "soa" => Ok(Kind::Soa),
+            "world" => Ok(Kind::World),
+            _ => Err(syn::Error::new_spanned(
+                name,
+                "expected `vec`, `front`, `set`, `map`, `sparse_matrix`, `histogram`, `sampler`, `cache`, `version_map`, `dir_tree`, `channel`, `segment_tree`, `union_find`, `binary_tree`, `rope`, `soa` or `world`",
+            )),
+        }
+    }
+}
+
+/// Generates what deriving `kind` for `input` does, its macros and any impls given by its `#[lit(...)]` attributes
+pub fn derive(input: DeriveInput, kind: Kind) -> TokenStream {
+    expand(input, Some(kind)).unwrap_or_else(|err| err.to_compile_error())
+}
+
+/// Generates what `#[derive(Lit)]` does for `input`, deriving the kind named by its `#[lit(kind = "...")]`
+pub fn derive_any(input: DeriveInput) -> TokenStream {
+    expand(input, None).unwrap_or_else(|err| err.to_compile_error())
+}
+
+fn expand(input: DeriveInput, kind: Option<Kind>) -> Result<TokenStream> {
+    let (mut options, mut errors) = Options::from_attrs(&input.attrs);
+    let mut push = |err| match &mut errors {
+        Some(errors) => errors.combine(err),
+        None => errors = Some(err),
+    };
+
+    if let Data::Struct(_) = input.data {
+    } else {
+        push(syn::Error::new(input.ident.span(), "expected a struct"));
+        return Err(errors.unwrap());
+    }
+    let kind = match (kind, options.lit_kind.take()) {
+        (Some(kind), None) | (None, Some((_, kind))) => kind,
+        (Some(kind), Some((name, _))) => {
+            push(syn::Error::new_spanned(
+                name,
+                "`#[lit(kind)]` only applies to `#[derive(Lit)]`",
+            ));
+            kind
+        }
+        // a malformed kind was already reported
+        (None, None) => {
+            return Err(errors.unwrap_or_else(|| {
+                syn::Error::new(
+                    input.ident.span(),
+                    "`#[derive(Lit)]` needs the kind of literal, like `#[lit(kind = \"vec\")]`",
+                )
+            }))
+        }
+    };
+    if options.kind.is_some() {
+        push(syn::Error::new(
+            input.ident.span(),
+            "naming a derive in `#[lit(...)]` only applies to the variants of an `EnumLit`",
+        ));
+    }
+    if let (Some(method), false) = (
+        &options.try_method,
+        matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set | Kind::Map),
+    ) {
+        push(syn::Error::new(
+            method.span(),
+            "`#[lit(try_method)]` only applies to `VecLit`, `VecFrontLit`, `SetLit` and `MapLit`",
+        ));
+        options.try_method = None;
+    }
+    if let (Some(method), false) = (
+        &options.track_caller,
+        matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set),
+    ) {
+        push(syn::Error::new(
+            method.span(),
+            "`#[lit(track_caller)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+        ));
+        options.track_caller = None;
+    }
+    if let (Some(validate), false) = (
+        &options.validate,
+        matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set),
+    ) {
+        push(syn::Error::new_spanned(
+            validate,
+            "`#[lit(validate)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+        ));
+        options.validate = None;
+    }
+    if let (Some(range), false) = (
+        &options.range,
+        matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set),
+    ) {
+        push(syn::Error::new_spanned(
+            range,
+            "`#[lit(range)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+        ));
+        options.range = None;
+    }
+    if let (Some(ty), false) = (
+        &options.non_zero,
+        matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set),
+    ) {
+        push(syn::Error::new_spanned(
+            ty,
+            "`#[lit(non_zero)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+        ));
+        options.non_zero = None;
+    }
+    if let (Some(ctor), false) = (&options.version, matches!(kind, Kind::VersionMap)) {
+        push(syn::Error::new_spanned(
+            ctor,
+            "`#[lit(version)]` only applies to `VersionMapLit`",
+        ));
+        options.version = None;
+    }
+    if options.const_len {
+        let message = if !matches!(kind, Kind::SegmentTree) {
+            Some("`#[lit(const_len)]` only applies to `SegmentTreeLit`")
+        } else if !matches!(
+            input.generics.params.iter().collect::<Vec<_>>()[..],
+            [GenericParam::Const(_)]
+        ) {
+            Some("`#[lit(const_len)]` needs the tree's only generic parameter to be its const length")
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            push(syn::Error::new(input.ident.span(), message));
+            options.const_len = false;
+        }
+    }
+    if options.sender && !matches!(kind, Kind::Channel) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(sender)]` only applies to `ChannelLit`",
+        ));
+        options.sender = false;
+    }
+    if options.push_macro && matches!(kind, Kind::BinaryTree) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(push_macro)]` doesn't apply to `BinaryTreeLit`, whose literals build whole trees",
+        ));
+        options.push_macro = false;
+    }
+    if let (Some(method), false) = (
+        &options.map_macro,
+        matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set),
+    ) {
+        push(syn::Error::new(
+            method.span(),
+            "`#[lit(map_macro)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+        ));
+        options.map_macro = None;
+    }
+    if options.reexport && options.export.is_none() {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(reexport)]` needs `#[lit(export)]`",
+        ));
+        options.reexport = false;
+    }
+    if options.reverse && !matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(reverse)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+        ));
+        options.reverse = false;
+    }
+    match (options.columns.first(), kind) {
+        (Some(column), kind) if !matches!(kind, Kind::Soa) => {
+            push(syn::Error::new(
+                column.span(),
+                "`#[lit(columns)]` only applies to `SoaLit`",
+            ));
+            options.columns.clear();
+        }
+        (None, Kind::Soa) => push(syn::Error::new(
+            input.ident.span(),
+            "`SoaLit` needs the push method of each column, like `#[lit(columns = \"push_x, push_y\")]`",
+        )),
+        _ => {}
+    }
+    if options.split_lines && !matches!(kind, Kind::Rope) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(split_lines)]` only applies to `RopeLit`",
+        ));
+        options.split_lines = false;
+    }
+    if options.strict && !matches!(kind, Kind::Set) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(strict)]` only applies to `SetLit`",
+        ));
+        options.strict = false;
+    }
+    if options.skip_none && !matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(skip_none)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+        ));
+        options.skip_none = false;
+    }
+    if let (Some(method), false) = (&options.fallback, matches!(kind, Kind::Map)) {
+        push(syn::Error::new(
+            method.span(),
+            "`#[lit(fallback)]` only applies to `MapLit`",
+        ));
+        options.fallback = None;
+    }
+    if options.key_into && !matches!(kind, Kind::Map) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(key_into)]` only applies to `MapLit`",
+        ));
+        options.key_into = false;
+    }
+    if options.value_into && !matches!(kind, Kind::Map) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(value_into)]` only applies to `MapLit`",
+        ));
+        options.value_into = false;
+    }
+    if options.own_keys && !matches!(kind, Kind::Map) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(own_keys)]` only applies to `MapLit`",
+        ));
+        options.own_keys = false;
+    }
+    if options.composite_keys && !matches!(kind, Kind::Map) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(composite_keys)]` only applies to `MapLit`",
+        ));
+        options.composite_keys = false;
+    }
+    if let (Some(wrap), false) = (&options.key_wrap, matches!(kind, Kind::Map)) {
+        push(syn::Error::new_spanned(
+            wrap,
+            "`#[lit(key_wrap)]` only applies to `MapLit`",
+        ));
+        options.key_wrap = None;
+    }
+    // an explicit `last` can't be told apart from the default, which is how histogram and cache literals behave anyway
+    if !matches!(options.on_duplicate, Duplicate::Last)
+        && !matches!(kind, Kind::Map | Kind::VersionMap)
+    {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(on_duplicate)]` only applies to `MapLit` and `VersionMapLit`",
+        ));
+        options.on_duplicate = Duplicate::Last;
+    }
+    if let (Some(path), false) = (
+        &options.tokens,
+        matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set),
+    ) {
+        push(syn::Error::new_spanned(
+            path,
+            "`#[lit(tokens)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+        ));
+        options.tokens = None;
+    }
+    if options.cow && !matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set) {
+        push(syn::Error::new(
+            input.ident.span(),
+            "`#[lit(cow)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+        ));
+        options.cow = false;
+    }
+    if let (Some((name, _)), false) = (
+        options.setters.first(),
+        matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set),
+    ) {
+        push(syn::Error::new(
+            name.span(),
+            "`#[lit(setter)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+        ));
+        options.setters.clear();
+    }
+    if let Some(ty) = &options.copy {
+        // the elements of these options aren't of the `Copy` type, so they can't be kept in its static array
+        let message = if !matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set) {
+            Some("`#[lit(copy)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`")
+        } else if options.skip_none || options.cow || options.tokens.is_some() {
+            Some("`#[lit(copy)]` can't be combined with `skip_none`, `cow` or `tokens`")
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            push(syn::Error::new_spanned(ty, message));
+            options.copy = None;
+        }
+    }
+
+    if let Some(elem) = &options.try_from {
+        let span = syn::spanned::Spanned::span(elem);
+        if !matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set) {
+            push(syn::Error::new(
+                span,
+                "`#[lit(try_from)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+            ));
+            options.try_from = None;
+        } else if options.new_args || options.async_new {
+            push(syn::Error::new(
+                span,
+                "`#[lit(try_from)]` can't be combined with `new_args` or `async_new`",
+            ));
+            options.try_from = None;
+        } else if options.capacity.is_none() {
+            push(syn::Error::new(
+                span,
+                "`#[lit(try_from)]` needs `#[lit(capacity = n)]`",
+            ));
+            options.try_from = None;
+        }
+    }
+
+    if let Some((key, val)) = &options.from_str {
+        let span = syn::spanned::Spanned::span(key);
+        let message = match (kind, val) {
+            (Kind::Vec | Kind::VecFront | Kind::Set, None) | (Kind::Map, Some(_)) => None,
+            (Kind::Vec | Kind::VecFront | Kind::Set, Some(_)) => {
+                Some("expected the element type, like `#[lit(from_str = \"T\")]`")
+            }
+            (Kind::Map, None) => {
+                Some("expected the key and value types, like `#[lit(from_str = \"K => V\")]`")
+            }
+            _ => Some(
+                "`#[lit(from_str)]` only applies to `VecLit`, `VecFrontLit`, `SetLit` and `MapLit`",
+            ),
+        };
+        let message = message.or(if options.new_args || options.async_new {
+            Some("`#[lit(from_str)]` can't be combined with `new_args` or `async_new`")
+        } else {
+            None
+        });
+        if let Some(message) = message {
+            push(syn::Error::new(span, message));
+            options.from_str = None;
+        }
+    }
+
+    if let Some((key, val)) = &options.fn_ctor {
+        let span = syn::spanned::Spanned::span(key);
+        let message = match (kind, val) {
+            (Kind::Vec | Kind::VecFront | Kind::Set, None) | (Kind::Map, Some(_)) => None,
+            (Kind::Vec | Kind::VecFront | Kind::Set, Some(_)) => {
+                Some("expected the element type, like `#[lit(fn = \"T\")]`")
+            }
+            (Kind::Map, None) => {
+                Some("expected the key and value types, like `#[lit(fn = \"K => V\")]`")
+            }
+            _ => {
+                Some("`#[lit(fn)]` only applies to `VecLit`, `VecFrontLit`, `SetLit` and `MapLit`")
+            }
+        };
+        let message = message.or(if options.new_args || options.async_new {
+            Some("`#[lit(fn)]` can't be combined with `new_args` or `async_new`")
+        } else {
+            None
+        });
+        if let Some(message) = message {
+            push(syn::Error::new(span, message));
+            options.fn_ctor = None;
+        }
+    }
+
+    if let Some((key, val)) = &options.traits {
+        let span = syn::spanned::Spanned::span(key);
+        let message = match (kind, val) {
+            (Kind::Vec | Kind::VecFront | Kind::Set, None) | (Kind::Map, Some(_)) => None,
+            (Kind::Vec | Kind::VecFront | Kind::Set, Some(_)) => {
+                Some("expected the element type, like `#[lit(traits = \"T\")]`")
+            }
+            (Kind::Map, None) => {
+                Some("expected the key and value types, like `#[lit(traits = \"K => V\")]`")
+            }
+            _ => Some(
+                "`#[lit(traits)]` only applies to `VecLit`, `VecFrontLit`, `SetLit` and `MapLit`",
+            ),
+        };
+        let message = message.or(if options.new_args || options.async_new {
+            Some("`#[lit(traits)]` can't be combined with `new_args` or `async_new`")
+        } else if options.strict {
+            Some("`#[lit(traits)]` can't be combined with `strict`, since `LitPush` doesn't say whether the element was new")
+        } else if options.inline_capacity.is_some() {
+            Some("`#[lit(traits)]` can't be combined with `inline_capacity`, since `LitNew` doesn't take a capacity")
+        } else {
+            None
+        });
+        if let Some(message) = message {
+            push(syn::Error::new(span, message));
+            options.traits = None;
+        }
+    }
+
+    if let Some(elem) = &options.from_slice {
+        let span = syn::spanned::Spanned::span(elem);
+        if !matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set) {
+            push(syn::Error::new(
+                span,
+                "`#[lit(from_slice)]` only applies to `VecLit`, `VecFrontLit` and `SetLit`",
+            ));
+            options.from_slice = None;
+        } else if options.new_args || options.async_new {
+            push(syn::Error::new(
+                span,
+                "`#[lit(from_slice)]` can't be combined with `new_args` or `async_new`",
+            ));
+            options.from_slice = None;
+        }
+    }
+
+    if let Some((key, _)) = &options.from_pairs {
+        let span = syn::spanned::Spanned::span(key);
+        let message = if !matches!(kind, Kind::Map) {
+            Some("`#[lit(from_pairs)]` only applies to `MapLit`")
+        } else if options.new_args || options.async_new {
+            Some("`#[lit(from_pairs)]` can't be combined with `new_args` or `async_new`")
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            push(syn::Error::new(span, message));
+            options.from_pairs = None;
+        }
+    }
+
+    if let Some(bulk) = &options.bulk {
+        let message = if !matches!(kind, Kind::Set | Kind::Map) {
+            Some("`#[lit(bulk)]` only applies to `SetLit` and `MapLit`")
+        } else if options.async_new {
+            Some("`#[lit(bulk)]` can't be combined with `async_new`")
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            push(syn::Error::new(bulk.span(), message));
+            options.bulk = None;
+        }
+    }
+    if let (Some(method), false) = (
+        &options.assert_eq,
+        matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set | Kind::Map),
+    ) {
+        push(syn::Error::new(
+            method.span(),
+            "`#[lit(assert_eq)]` only applies to `VecLit`, `VecFrontLit`, `SetLit` and `MapLit`",
+        ));
+        options.assert_eq = None;
+    }
+    if let (Some(method), false) = (
+        &options.to_lit_string,
+        matches!(kind, Kind::Vec | Kind::VecFront | Kind::Set | Kind::Map),
+    ) {
+        push(syn::Error::new(
+            method.span(),
+            "`#[lit(to_lit_string)]` only applies to `VecLit`, `VecFrontLit`, `SetLit` and `MapLit`",
+        ));
+        options.to_lit_string = None;
+    }
+
+    // the macro is generated despite any errors in the options, so its invocations don't fail too
+    let mut tokens = match &options.fn_ctor {
+        Some((key, val)) => gate(
+            &options,
+            vec![fn_impl(&input, kind, key, val.as_ref(), &options)],
+        ),
+        None => generate(&input.ident, &input.generics, kind, &options),
+    };
+    if let (Some(elem), Some(capacity)) = (&options.try_from, &options.capacity) {
+        tokens.extend(gate(
+            &options,
+            try_from_impls(&input, kind, elem, capacity, &options),
+        ));
+    }
+    if let (Some(method), Ok(macro_name)) =
+        (&options.to_lit_string, macro_ident(&input.ident, &options))
+    {
+        tokens.extend(gate(
+            &options,
+            vec![to_lit_string_impl(
+                &input,
+                kind,
+                method,
+                &macro_name,
+                &options,
+            )],
+        ));
+    }
+    if let Some((key, val)) = &options.traits {
+        tokens.extend(gate(
+            &options,
+            trait_impls(&input, kind, key, val.as_ref(), &options),
+        ));
+    }
+    if let Some(elem) = &options.from_slice {
+        tokens.extend(gate(
+            &options,
+            vec![from_slice_impl(&input, kind, elem, &options)],
+        ));
+    }
+    if let Some((key, val)) = &options.from_pairs {
+        tokens.extend(gate(
+            &options,
+            vec![from_pairs_impl(&input, key, val, &options)],
+        ));
+    }
+    if let Some((key, val)) = &options.from_str {
+        tokens.extend(gate(
+            &options,
+            from_str_impl(&input, kind, key, val.as_ref(), &options),
+        ));
+    }
+    if let Some(errors) = errors {
+        tokens.extend(errors.to_compile_error());
+    }
+    Ok(tokens)
+}
+
+/// Generates what deriving `EnumLit` for `input` does, a macro for each variant whose `#[lit(...)]` names a derive
+///
+/// The macro builds the variant's collection like the derive's macro would and wraps it in the variant.
+pub fn derive_enum(input: DeriveInput) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    let mut errors: Option<syn::Error> = None;
+    let mut push = |err| match &mut errors {
+        Some(errors) => errors.combine(err),
+        None => errors = Some(err),
+    };
+
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => return syn::Error::new(input.ident.span(), "expected an enum").to_compile_error(),
+    };
+    if let Some(attr) = input.attrs.iter().find(|attr| attr.path.is_ident("lit")) {
+        push(syn::Error::new_spanned(
+            attr,
+            "the `#[lit(...)]` of an `EnumLit` go on its variants",
+        ));
+    }
+    if !input.generics.params.is_empty() {
+        push(syn::Error::new_spanned(
+            &input.generics,
+            "`EnumLit` doesn't support generic enums",
+        ));
+    }
+
+    for variant in variants {
+        let (options, variant_errors) = Options::from_attrs(&variant.attrs);
+        if let Some(err) = variant_errors {
+            push(err);
+        }
+        let lit_kind = options.lit_kind.as_ref().map(|(_, kind)| *kind);
+        let kind = match options.kind.or(lit_kind) {
+            Some(kind) => kind,
+            None => continue,
+        };
+        let ty = match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0].ty,
+            _ => {
+                push(syn::Error::new(
+                    variant.ident.span(),
+                    "expected a variant with a single unnamed field",
+                ));
+                continue;
+            }
+        };
+        if options.try_method.is_some()
+            || options.try_from.is_some()
+            || options.from_str.is_some()
+            || options.fn_ctor.is_some()
+            || options.from_slice.is_some()
+            || options.from_pairs.is_some()
+            || options.traits.is_some()
+            || options.to_lit_string.is_some()
+            || options.assert_eq.is_some()
+            || options.export.is_some()
+        {
+            push(syn::Error::new(
+                variant.ident.span(),
+                "`try_method`, `try_from`, `from_str`, `fn`, `from_slice`, `from_pairs`, `traits`, `to_lit_string`, `assert_eq` and `export` don't apply to `EnumLit` variants",
+            ));
+        }
+
+        if options.strict && !matches!(kind, Kind::Set) {
+            push(syn::Error::new(
+                variant.ident.span(),
+                "`#[lit(strict)]` only applies to `SetLit`",
+            ));
+        }
+
+        let name = format_ident!("{}{}", input.ident.unraw(), variant.ident.unraw());
+        let macro_name = match macro_ident(&name, &options) {
+            Ok(macro_name) => macro_name,
+            Err(err) => {
+                push(err);
+                continue;
+            }
+        };
+        let enum_name = &input.ident;
+        let variant_name = &variant.ident;
+        let target = Target {
+            path: quote!(<#ty>),
+            generic: None,
+            wrap: Some(quote!(#enum_name::#variant_name)),
+            extend: false,
+        };
+        let body = if !options.sections.is_empty() {
+            sections_body(&target, &quote!(#macro_name), &options)
+        } else {
+            kind_body(&target, kind, &options)
+        };
+        tokens.extend(gate(&options, macro_def(&macro_name, body, true, false)));
+    }
+
+    if let Some(errors) = errors {
+        tokens.extend(errors.to_compile_error());
+    }
+    tokens
+}
+
+/// Generates the literal macro for the type `name`
+pub fn generate(name: &Ident, generics: &Generics, kind: Kind, options: &Options) -> TokenStream {
+    let macro_name = match macro_ident(name, options) {
+        Ok(macro_name) => macro_name,
+        Err(err) => return err.to_compile_error(),
+    };
+    // an exported macro can be invoked from other crates, where the type is only found through `$crate`
+    let module = options
+        .export
+        .as_ref()
+        .map(|segments| quote!($crate #(::#segments)*));
+    let name_path = match &module {
+        Some(module) => quote!(#module::#name),
+        None => quote!(#name),
+    };
+    let target = Target {
+        path: default_path(&name_path, generics),
+        // type parameters that can't be inferred, like those only used by `PhantomData`, can then be given
+        generic: generics.type_params().next().map(|_| name_path.clone()),
+        wrap: None,
+        extend: false,
+    };
+    // an exported macro is invoked by its path wherever it expands, rather than needing to be imported, which for one
+    // exported from the crate root is that of a hidden re-export, since the root one can't be used by path in its crate
+    let self_alias = format_ident!("__derive_lit_{}", macro_name.unraw());
+    let macro_path = match (&module, options.reexport) {
+        (Some(module), true) => quote!(#module::#macro_name),
+        (Some(module), false) => quote!(#module::#self_alias),
+        (None, _) => quote!(#macro_name),
+    };
+    let def = |macro_name: &Ident, body: TokenStream, import: bool| match &options.export {
+        Some(segments) if options.reexport => reexport_def(macro_name, body, segments),
+        export => macro_def(macro_name, body, import, export.is_some()),
+    };
+
+    let body = if !options.sections.is_empty() {
+        sections_body(&target, &macro_path, options)
+    } else {
+        kind_body(&target, kind, options)
+    };
+
+    // a type already named like its macro would clash with the import
+    let import = macro_name.unraw() != name.unraw();
+    let mut items = def(&macro_name, body, import);
+    if options.export.is_some() && !options.reexport {
+        items.push(quote! {
+            #[doc(hidden)]
+            pub use #macro_name as #self_alias;
+        });
+    }
+    if options.push_macro {
+        let push_name = Ident::new(&format!("{}_push", macro_name.unraw()), macro_name.span());
+        let target = Target {
+            extend: true,
+            ..target.clone()
+        };
+        let body = if !options.sections.is_empty() {
+            sections_body(&target, &macro_path, options)
+        } else {
+            kind_body(&target, kind, options)
+        };
+        items.extend(def(&push_name, body, true));
+    }
+    if let Some(method) = &options.map_macro {
+        let map_name = Ident::new(&format!("{}_map", macro_name.unraw()), macro_name.span());
+        let body = map_body(&target, &quote!(#method), options, true);
+        items.extend(def(&map_name, body, true));
+    }
+    if let Some(method) = &options.assert_eq {
+        let assert_name = Ident::new(
+            &format!("assert_{}_eq", macro_name.unraw()),
+            macro_name.span(),
+        );
+        let body = assert_body(&macro_name, &macro_path, kind, method);
+        items.extend(def(&assert_name, body, true));
+    }
+    if let Some(method) = &options.try_method {
+        let try_name = Ident::new(&format!("try_{}", macro_name.unraw()), macro_name.span());
+        let body = try_body(&target, kind, method, options);
+        items.extend(def(&try_name, body, true));
+    }
+    gate(options, items)
+}
+
+/// The path of the type `name` that constructors are called on by literals without generic arguments
+///
+/// If some of its parameters have defaults, the others are given as `_` so that the defaults apply, which they only do
+/// in a type.
+fn default_path(name: &TokenStream, generics: &Generics) -> TokenStream {
+    let has_default = generics.params.iter().any(|param| match param {
+        GenericParam::Type(param) => param.default.is_some(),
+        GenericParam::Const(param) => param.default.is_some(),
+        GenericParam::Lifetime(_) => false,
+    });
+    if !has_default {
+        return quote!(#name);
+    }
+
+    let inferred = generics.params.iter().filter_map(|param| match param {
+        GenericParam::Type(TypeParam { default: None, .. })
+        | GenericParam::Const(ConstParam { default: None, .. }) => Some(quote!(_)),
+        _ => None,
+    });
+    quote!(<#name<#(#inferred),*>>)
+}
+
+/// The name of the macro for the type `name`, its snake case unless renamed, which is a raw identifier if it's a keyword
+pub fn macro_ident(name: &Ident, options: &Options) -> Result<Ident> {
+    let (macro_name, span) = match &options.rename {
+        Some(rename) => (rename.unraw().to_string(), rename.span()),
+        None if options.merge_acronyms => (
+            snake_case_merging_acronyms(&name.unraw().to_string()),
+            name.span(),
+        ),
+        None => (name.unraw().to_string().to_snake_case(), name.span()),
+    };
+    match macro_name.as_str() {
+        "crate" | "self" | "super" => Err(syn::Error::new(
+            span,
+            format!(
+                "the macro can't be named `{}`, which is a keyword",
+                macro_name
+            ),
+        )),
+        _ if KEYWORDS.contains(&macro_name.as_str()) => Ok(Ident::new_raw(&macro_name, span)),
+        _ => Ok(Ident::new(&macro_name, span)),
+    }
+}
+
+/// The snake case of `name` with only lowercase letters and digits ending words, so a run of capitals stays one word
+/// with the capitalized word after it
+fn snake_case_merging_acronyms(name: &str) -> String {
+    let mut snake = String::new();
+    let mut prev = None;
+    for c in name.chars() {
+        if c.is_uppercase()
+            && matches!(prev, Some(p) if char::is_lowercase(p) || char::is_numeric(p))
+        {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+        prev = Some(c);
+    }
+    snake
+}
+
+/// The keywords of every edition, including the reserved ones
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// The macro arms for the literal syntax of `kind`
+fn kind_body(target: &Target, kind: Kind, options: &Options) -> TokenStream {
+    // a literal of only literal elements or keys is sorted into the bulk constructor, and others fall through to the
+    // arms after it
+    let mut body = match (&options.bulk, target.extend) {
+        (Some(bulk), false) => bulk_body(target, bulk, kind, options),
+        _ => TokenStream::new(),
+    };
+    body.extend(match kind {
+        // with `#[lit(traits)]`, elements and entries are added through the traits instead of the methods they wrap
+        Kind::Vec | Kind::VecFront | Kind::Set if options.traits.is_some() => {
+            elem_body(target, &quote!(lit_push), options)
+        }
+        Kind::Map if options.traits.is_some() => {
+            map_body(target, &quote!(lit_insert), options, true)
+        }
+        Kind::Vec => elem_body(target, &quote!(push), options),
+        Kind::VecFront => elem_body(target, &quote!(push_front), options),
+        Kind::Set => elem_body(target, &quote!(insert), options),
+        Kind::Map => map_body(target, &quote!(insert), options, true),
+        Kind::SparseMatrix => triplet_body(target, &quote!(insert), options),
+        Kind::Histogram => histogram_body(target, options),
+        Kind::Sampler => weighted_body(target, &quote!(add), options),
+        Kind::Cache => cache_body(target, &quote!(insert), options),
+        Kind::VersionMap => version_map_body(target, &quote!(insert), options),
+        Kind::DirTree => dir_tree_body(target, options),
+        Kind::Channel => channel_body(target, options),
+        Kind::SegmentTree => segment_tree_body(target, options),
+        Kind::UnionFind => union_find_body(target, options),
+        Kind::BinaryTree => binary_tree_body(target, options),
+        Kind::Rope => elem_body(target, &quote!(push_str), options),
+        Kind::Soa => soa_body(target, options),
+        Kind::World => world_body(target, options),
+    });
+    body
+}
+
+/// The arms for literals of only literal elements, or of entries with literal keys, which are sorted by `__sorted!`
+/// and passed to the bulk constructor given by `#[lit(bulk = "...")]` as an array
+fn bulk_body(target: &Target, bulk: &Ident, kind: Kind, options: &Options) -> TokenStream {
+    let (pattern, entries) = match kind {
+        Kind::Map => (
+            quote!($($key:literal => $val:expr),*),
+            quote!($($key => $val),*),
+        ),
+        _ => (quote!($($key:literal),*), quote!($($key),*)),
+    };
+    let own_keys = if options.own_keys {
+        quote!(own_keys)
+    } else {
+        quote!()
+    };
+    let key_into = if options.key_into {
+        quote!(key_into)
+    } else {
+        quote!()
+    };
+    let key_wrap = options
+        .key_wrap
+        .as_ref()
+        .map(|wrap| quote!(key_wrap = #wrap));
+    let value_into = if options.value_into {
+        quote!(value_into)
+    } else {
+        quote!()
+    };
+    let flags = quote!([#own_keys #key_into #key_wrap #value_into]);
+
+    let bulk_ctors = [Ctor {
+        head: quote!(),
+        new: New::Call(quote!(#bulk)),
+    }];
+    let form = Form {
+        pattern,
+        each: None,
+        build: Box::new(move |bulk| {
+            quote! {
+                ::derive_lit::__sorted!({ #bulk } #flags; #entries)
+            }
+        }),
+    };
+
+    ctor_arms(target, options, &bulk_ctors, &[&form])
+}
+
+/// Defines the macro `macro_name` with the given arms, as the items to be gated together
+///
+/// With `import`, the macro is also imported into the module, which makes it an item of the module so it can be
+/// invoked above its definition, where the textual scope of `macro_rules!` hasn't started yet. With `export`, it's also
+/// exported from the crate root for other crates.
+pub(crate) fn macro_def(
+    macro_name: &Ident,
+    body: TokenStream,
+    import: bool,
+    export: bool,
+) -> Vec<TokenStream> {
+    let export = if export {
+        quote!(#[macro_export])
+    } else {
+        quote!()
+    };
+    let mut items = vec![quote! {
+        #export
+        macro_rules! #macro_name {
+            #body
+        }
+    }];
+    if import {
+        items.push(quote! {
+            #[allow(unused_imports)]
+            pub(crate) use #macro_name;
+        });
+    }
+    items
+}
+
+/// Defines the macro `macro_name` for `#[lit(reexport)]`, exported from the crate root under a hidden name unique to
+/// the type's module, `segments`, and re-exported from the module under its own name
+fn reexport_def(macro_name: &Ident, body: TokenStream, segments: &[Ident]) -> Vec<TokenStream> {
+    let mut hidden = String::from("__derive_lit_");
+    for segment in segments {
+        hidden.push_str(&format!("_{}_", segment.unraw()));
+    }
+    let hidden = format_ident!("{}_{}", hidden, macro_name.unraw());
+    vec![
+        quote! {
+            #[doc(hidden)]
+            #[macro_export]
+            macro_rules! #hidden {
+                #body
+            }
+        },
+        quote! {
+            pub use #hidden as #macro_name;
+        },
+    ]
+}
+
+/// One way of writing the entries of a literal
+pub(crate) struct Form {
+    /// The pattern matching the entries
+    pattern: TokenStream,
+    /// A metavariable repeated once per entry, for counting the entries
+    each: Option<TokenStream>,
+    /// Builds the expansion of the macro from the expression constructing an empty value
+    build: Box<dyn Fn(&TokenStream) -> TokenStream>,
+}
+
+impl Form {
+    /// A form whose expansion constructs `temp` and then runs `stmts` on it
+    fn new(pattern: TokenStream, each: Option<TokenStream>, stmts: TokenStream) -> Self {
+        Form {
+            pattern,
+            each,
+            build: Box::new(move |new| {
+                // an empty literal doesn't add anything to `temp`
+                quote! {
+                    {
+                        #[allow(unused_mut)]
+                        let mut temp = #new;
+                        #stmts
+                        temp
+                    }
+                }
+            }),
+        }
+    }
+}
+
+/// The type constructed by a literal
+#[derive(Clone)]
+pub(crate) struct Target {
+    /// The path the constructors are called on
+    pub path: TokenStream,
+    /// The type that generic arguments are applied to when a literal starts with `::<...>`, if that's accepted
+    pub generic: Option<TokenStream>,
+    /// The path of the tuple variant that the constructed value is wrapped in, for `EnumLit`
+    pub wrap: Option<TokenStream>,
+    /// Whether the literal starts with `target;` and adds to the existing value `target`, as for `my_struct_push!`
+    pub extend: bool,
+}
+
+/// A way of constructing the empty value at the start of a literal
+pub(crate) struct Ctor {
+    /// The pattern matching the head of the literal, before its entries
+    head: TokenStream,
+    new: New,
+}
+
+/// How a constructor makes the value that the entries are added to
+pub(crate) enum New {
+    /// The default constructor, `new()` unless configured otherwise
+    Default,
+    /// A call of the constructor on the type
+    Call(TokenStream),
+    /// An existing value, which the literal extends
+    Seed(TokenStream),
+}
+
+/// Generates an arm for every combination of constructor and form, in order
+///
+/// If the target accepts generic arguments, the arms are preceded by copies of them that start with `::<...>` and
+/// construct the type with those arguments. The paths of `#[lit(use = "...")]` are imported around each expansion, and
+/// `#[lit(csv = "...")]` adds a form before the others, for literals of any kind.
+fn arms(target: &Target, options: &Options, ctors: &[Ctor], forms: &[Form]) -> TokenStream {
+    let csv = options.csv.as_ref().map(|method| {
+        let method = method.clone();
+        Form {
+            pattern: quote!(csv $csv:literal),
+            each: None,
+            build: Box::new(move |new| {
+                quote! {
+                    ::derive_lit::__csv!({ #new } #method $csv)
+                }
+            }),
+        }
+    });
+    let forms: Vec<_> = csv.iter().chain(forms).collect();
+
+    ctor_arms(target, options, ctors, &forms)
+}
+
+/// Generates an arm for every combination of constructor and form, in order, as `arms` does but without any form of its
+/// own
+fn ctor_arms(target: &Target, options: &Options, ctors: &[Ctor], forms: &[&Form]) -> TokenStream {
+    let extend = [Ctor {
+        head: quote!($target:expr;),
+        new: New::Seed(quote!(&mut $target)),
+    }];
+    let ctors = if target.extend { &extend[..] } else { ctors };
+
+    let mut heads = vec![];
+    if let (Some(generic), false) = (&target.generic, target.extend) {
+        heads.push((quote!(::<$($param:ty),*>), quote!(<#generic<$($param),*>>)));
+    }
+    heads.push((quote!(), target.path.clone()));
+
+    let mut arms = TokenStream::new();
+    for (generics, path) in &heads {
+        for ctor in ctors {
+            for form in forms {
+                let head = &ctor.head;
+                let pattern = &form.pattern;
+                let new = match &ctor.new {
+                    New::Default => new_expr(path, options, form.each.as_ref()),
+                    New::Call(new) => quote!(#path::#new),
+                    New::Seed(seed) => seed.clone(),
+                };
+                // a seed already exists, so there's no constructor to await
+                let new = match &ctor.new {
+                    New::Seed(_) => new,
+                    _ if options.async_new => quote!((#new).await),
+                    _ => new,
+                };
+                let mut expansion = (form.build)(&new);
+                if let Some(wrap) = &target.wrap {
+                    expansion = quote!(#wrap(#expansion));
+                }
+                let mut uses: Vec<_> = options.uses.iter().map(|path| quote!(#path)).collect();
+                if options.traits.is_some() {
+                    uses.extend(vec![
+                        quote!(::derive_lit_core::LitNew as _),
+                        quote!(::derive_lit_core::LitPush as _),
+                        quote!(::derive_lit_core::LitInsert as _),
+                    ]);
+                }
+                if !uses.is_empty() {
+                    expansion = quote! {
+                        {
+                            #(
+                                #[allow(unused_imports)]
+                                use #uses;
+                            )*
+                            #expansion
+                        }
+                    };
+                }
+                if target.extend {
+                    expansion = quote!({ #expansion; });
+                }
+
+                arms.extend(quote! {
+                    ( #generics #head #pattern ) => {
+                        #expansion
+                    };
+                });
+            }
+        }
+    }
+    arms
+}
+
+/// The constructors of a literal, which are `from base;` and those configured by `#[lit(new_in)]` and
+/// `#[lit(ctor(...))]`, with the keyword constructors before the default one
+fn ctors(options: &Options) -> Vec<Ctor> {
+    let seed = if options.clone_seed {
+        quote!(::std::clone::Clone::clone(&$base))
+    } else {
+        quote!($base)
+    };
+    let mut ctors = vec![Ctor {
+        head: quote!(from $base:expr;),
+        new: New::Seed(seed),
+    }];
+    if options.new_in {
+        ctors.push(Ctor {
+            head: quote!(in $alloc:expr;),
+            new: New::Call(quote!(new_in($alloc))),
+        });
+    }
+    ctors.extend(options.ctors.iter().map(|(method, keyword)| Ctor {
+        head: quote!(#keyword $($arg:expr),*;),
+        new: New::Call(quote!(#method($($arg),*))),
+    }));
+
+    ctors.push(Ctor {
+        head: if options.new_args {
+            quote!($($arg:expr),*;)
+        } else {
+            quote!()
+        },
+        new: New::Default,
+    });
+    ctors
+}
+
+/// The macro arms for literals that are a plain list of elements
+pub(crate) fn elem_body(target: &Target, method: &TokenStream, options: &Options) -> TokenStream {
+    let mut elem_ctors = vec![Ctor {
+        head: quote!(cap = $cap:expr;),
+        new: New::Call(capacity_call(options, &quote!($cap))),
+    }];
+    elem_ctors.extend(ctors(options));
+
+    // with `track_caller`, the location is resolved at the literal, wherever in its expansion it's taken
+    let (method, origin) = match &options.track_caller {
+        Some(track_caller) => (
+            quote!(#track_caller),
+            quote!(, ::std::panic::Location::caller()),
+        ),
+        None => (method.clone(), quote!()),
+    };
+
+    // `what` describes the element in the panic of a `strict` literal
+    let trace = trace_stmt(options.trace.as_ref(), "{:?}", quote!(&elem));
+    let add = |what: TokenStream| {
+        let insert = if options.strict {
+            quote! {
+                #trace
+                if !temp.#method(elem #origin) {
+                    panic!("duplicate element {} in literal", #what);
+                }
+            }
+        } else {
+            quote! {
+                #trace
+                temp.#method(elem #origin);
+            }
+        };
+        if options.skip_none {
+            quote! {
+                if let Some(elem) = elem {
+                    #insert
+                }
+            }
+        } else {
+            insert
+        }
+    };
+    let add_iter = add(quote!(concat!("from `", stringify!($iter), "`")));
+    let add_elem = add(quote!(concat!("`", stringify!($elem), "`")));
+
+    let iter = if options.reverse {
+        reversed(quote!($iter))
+    } else {
+        quote!($iter)
+    };
+    let from_form = Form::new(
+        quote!(from $iter:expr),
+        None,
+        quote! {
+            for elem in #iter {
+                #add_iter
+            }
+        },
+    );
+
+    // elements are passed to closures whose parameter type is already inferred from the method,
+    // so a mistyped element is reported at the element itself
+    let repeat_form = Form::new(
+        quote!($elem:expr; $n:expr),
+        None,
+        quote! {
+            let mut add = |elem| {
+                #add_elem
+            };
+            let mut repeat = |repeated, n: usize| {
+                for _ in 1..n {
+                    add(::std::clone::Clone::clone(&repeated));
+                }
+                if n > 0 {
+                    add(repeated);
+                }
+            };
+            repeat($elem, $n);
+        },
+    );
+
+    let elem_form = Form::new(
+        quote!($( $elem:expr ),*),
+        Some(quote!($elem)),
+        // going through a one-element array lets `#[cfg]` on an element remove it, since an
+        // `expr` fragment can't be told apart from an attribute in front of it, and each element
+        // gets its own closure so that elements of different types can go to a generic method,
+        // while a mistyped element is still reported at the element itself
+        quote! {
+            #[allow(dead_code)]
+            fn each<T, const N: usize>(add: &mut impl FnMut(T), elems: [T; N]) {
+                for elem in elems {
+                    add(elem);
+                }
+            }
+            $(
+                each(&mut |elem| { #add_elem }, [$elem]);
+            )*
+        },
+    );
+
+    // constant elements of a `Copy` type are stored once in a static, rather than each being
+    // pushed by its own code
+    let elem_form = match &options.copy {
+        Some(ty) if !options.strict => {
+            let add = add(quote!());
+            Form::new(
+                quote!($( $elem:expr ),*),
+                Some(quote!($elem)),
+                quote! {
+                    // a slice, since the length of the array would count elements that `#[cfg]` removes
+                    static ELEMS: &[#ty] = &[$($elem),*];
+                    for &elem in ELEMS.iter() {
+                        #add
+                    }
+                },
+            )
+        }
+        _ => elem_form,
+    };
+
+    // anything else, like `in` elements and setters, is handled by `__elems!`
+    let skip_none = if options.skip_none {
+        quote!(skip_none)
+    } else {
+        quote!()
+    };
+    let strict = if options.strict {
+        quote!(strict)
+    } else {
+        quote!()
+    };
+    let sort = if options.sort { quote!(sort) } else { quote!() };
+    let reverse = if options.reverse {
+        quote!(reverse)
+    } else {
+        quote!()
+    };
+    let cow = if options.cow { quote!(cow) } else { quote!() };
+    let split_lines = if options.split_lines {
+        quote!(split_lines)
+    } else {
+        quote!()
+    };
+    let splat = match (options.splat, &options.splat_str) {
+        (_, Some(method)) => quote!(splat = #method),
+        (true, None) => quote!(splat),
+        (false, None) => quote!(),
+    };
+    let track_caller = if options.track_caller.is_some() {
+        quote!(track_caller)
+    } else {
+        quote!()
+    };
+    let tokens = options.tokens.as_ref().map(|path| quote!(tokens = #path));
+    let trace = options.trace.as_ref().map(|path| quote!(trace = #path));
+    let validate = options
+        .validate
+        .as_ref()
+        .map(|path| quote!(validate = #path));
+    let range = options.range.as_ref().map(|range| quote!(range = (#range)));
+    let non_zero = options.non_zero.as_ref().map(|ty| quote!(non_zero = (#ty)));
+    let setters = options
+        .setters
+        .iter()
+        .map(|(name, method)| quote!(setter(#name = #method)));
+    let flags = quote!([#skip_none #strict #sort #reverse #cow #split_lines #splat #track_caller #tokens #trace #validate #range #non_zero #(#setters)*]);
+    let extended_form = Form {
+        pattern: quote!($($elems:tt)*),
+        each: None,
+        build: Box::new(move |new| {
+            quote! {
+                ::derive_lit::__elems!({ #new } #method #flags; $($elems)*)
+            }
+        }),
+    };
+
+    // only `__elems!` can tell which elements are string literals for `cow`, `split_lines` and `splat`, or byte
+    // strings, and sort, reverse, check or convert them
+    let forms = if options.tokens.is_some()
+        || options.validate.is_some()
+        || options.range.is_some()
+        || options.non_zero.is_some()
+        || options.cow
+        || options.split_lines
+        || options.splat
+        || options.sort
+        || options.reverse
+    {
+        vec![from_form, extended_form]
+    } else {
+        vec![from_form, repeat_form, elem_form, extended_form]
+    };
+
+    arms(target, options, &elem_ctors, &forms)
+}
+
+/// The items of `iter`, collected so that they can be iterated in reverse whatever the iterator
+pub(crate) fn reversed(iter: TokenStream) -> TokenStream {
+    quote! {
+        ::std::iter::Iterator::rev(::std::iter::IntoIterator::into_iter(
+            ::std::iter::Iterator::collect::<::std::vec::Vec<_>>(
+                ::std::iter::IntoIterator::into_iter(#iter),
+            ),
+        ))
+    }
+}
+
+/// The macro arms for literals that are a list of `key => val` entries
+///
+/// Unless `extended` is set, the arms end without the catch-all arm for `__entries!`, so more arms can follow.
+pub(crate) fn map_body(
+    target: &Target,
+    method: &TokenStream,
+    options: &Options,
+    extended: bool,
+) -> TokenStream {
+    let on_duplicate = options.on_duplicate;
+    let temp = quote!(temp);
+    let trace = options.trace.as_ref();
+    let insert_from = insert_entry(
+        &temp,
+        method,
+        on_duplicate,
+        trace,
+        &quote!(key),
+        &quote!(val),
+    );
+    let key = key_expr(options.key_wrap.as_ref(), options.key_into, quote!($key));
+    let val = into_expr(options.value_into, quote!($val));
+    let insert = insert_entry(&temp, method, on_duplicate, trace, &key, &val);
+    let default = into_expr(options.value_into, quote!($default));
+
+    let mut forms = vec![Form::new(
+        quote!(from $iter:expr),
+        None,
+        quote! {
+            for (key, val) in $iter {
+                #insert_from
+            }
+        },
+    )];
+    // the keys and values are counted as in `new_expr`, to check at compile time that they pair up
+    let count = |each: TokenStream| quote!(<[&str]>::len(&[$(stringify!(#each)),*]));
+    let (zip_keys, zip_vals) = (count(quote!($zip_key)), count(quote!($zip_val)));
+    let zip_key = key_expr(options.key_wrap.as_ref(), options.key_into, quote!(key));
+    let zip_val = into_expr(options.value_into, quote!(val));
+    let insert_zip = insert_entry(&temp, method, on_duplicate, trace, &zip_key, &zip_val);
+    forms.push(Form::new(
+        quote!(keys: [$($zip_key:expr),* $(,)?]; values: [$($zip_val:expr),* $(,)?]),
+        Some(quote!($zip_key)),
+        quote! {
+            const _: () = ::std::assert!(
+                #zip_keys == #zip_vals,
+                "the `keys` and `values` of a literal must have the same length"
+            );
+            let keys = [$($zip_key),*];
+            let vals = [$($zip_val),*];
+            for (key, val) in ::std::iter::IntoIterator::into_iter(keys).zip(vals) {
+                #insert_zip
+            }
+        },
+    ));
+    // `key => ..iter` would match these as a range, so multimap literals are left to `__entries!`, as are literals
+    // with owned, sorted or dotted keys since only `__entries!` can tell which keys are literals
+    let plain = !options.multi && !options.own_keys && !options.sort && options.dotted.is_none();
+    if let (Some(fallback), true) = (&options.fallback, plain) {
+        forms.push(Form::new(
+            quote!($($key:expr => $val:expr,)* _ => $default:expr),
+            Some(quote!($key)),
+            quote! {
+                $(
+                    #insert
+                )*
+                temp.#fallback(#default);
+            },
+        ));
+    }
+    if plain {
+        forms.push(map_form(method, on_duplicate, trace, &key, &val));
+    }
+
+    if !extended {
+        return arms(target, options, &ctors(options), &forms);
+    }
+
+    // anything else, like `fmt` keys, is handled by `__entries!`
+    let fallback = options
+        .fallback
+        .as_ref()
+        .map(|fallback| quote!(fallback = #fallback));
+    let multi = if options.multi {
+        quote!(multi)
+    } else {
+        quote!()
+    };
+    let sort = if options.sort { quote!(sort) } else { quote!() };
+    let on_duplicate = on_duplicate.ident();
+    let composite_keys = if options.composite_keys {
+        quote!(composite_keys)
+    } else {
+        quote!()
+    };
+    let own_keys = if options.own_keys {
+        quote!(own_keys)
+    } else {
+        quote!()
+    };
+    let key_into = if options.key_into {
+        quote!(key_into)
+    } else {
+        quote!()
+    };
+    let value_into = if options.value_into {
+        quote!(value_into)
+    } else {
+        quote!()
+    };
+    let key_wrap = options
+        .key_wrap
+        .as_ref()
+        .map(|path| quote!(key_wrap = #path));
+    let dotted = options.dotted.as_ref().map(|path| quote!(dotted = #path));
+    let trace = options.trace.as_ref().map(|path| quote!(trace = #path));
+    let json_value = options
+        .json_value
+        .as_ref()
+        .map(|path| quote!(json_value = #path));
+    let flags = quote!([#fallback #multi #sort #composite_keys #own_keys #key_into #key_wrap #value_into #dotted #trace #json_value on_duplicate = #on_duplicate]);
+    let method = method.clone();
+    forms.push(Form {
+        pattern: quote!($($entries:tt)*),
+        each: None,
+        build: Box::new(move |new| {
+            quote! {
+                ::derive_lit::__entries!({ #new } #method #flags; $($entries)*)
+            }
+        }),
+    });
+
+    arms(target, options, &ctors(options), &forms)
+}
+
+/// The form of a plain list of `$key => $val` entries, inserting the `key` and `val` expressions of each
+fn map_form(
+    method: &TokenStream,
+    on_duplicate: Duplicate,
+    trace: Option<&Path>,
+    key: &TokenStream,
+    val: &TokenStream,
+) -> Form {
+    let insert = insert_entry(&quote!(temp), method, on_duplicate, trace, key, val);
+
+    Form::new(
+        quote!($($key:expr => $val:expr),*),
+        Some(quote!($key)),
+        quote! {
+            $(
+                #insert
+            )*
+        },
+    )
+}
+
+/// `expr` converted with `Into` if `into` is set, as by `#[lit(key_into)]` and `#[lit(value_into)]`
+pub(crate) fn into_expr(into: bool, expr: TokenStream) -> TokenStream {
+    if into {
+        quote!(::std::convert::Into::into(#expr))
+    } else {
+        expr
+    }
+}
+
+/// The key `expr` wrapped in `wrap` if it's given, as by `#[lit(key_wrap = "...")]`, and then converted with `Into` if
+/// `into` is set
+pub(crate) fn key_expr(wrap: Option<&Path>, into: bool, expr: TokenStream) -> TokenStream {
+    let expr = match wrap {
+        Some(wrap) => quote!(#wrap(#expr)),
+        None => expr,
+    };
+    into_expr(into, expr)
+}
+
+/// The statement inserting an entry into `temp`, following the `#[lit(on_duplicate = "...")]` policy
+pub(crate) fn insert_entry(
+    temp: &TokenStream,
+    method: &TokenStream,
+    on_duplicate: Duplicate,
+    trace: Option<&Path>,
+    key: &TokenStream,
+    val: &TokenStream,
+) -> TokenStream {
+    let trace = trace_stmt(trace, "{:?} => {:?}", quote!(&key, &val));
+
+    // the key and value are passed to a closure typed by its body, so that a mistyped entry is
+    // reported at the entry rather than at the insertion
+    match on_duplicate {
+        Duplicate::Last if trace.is_empty() => quote!(#temp.#method(#key, #val);),
+        Duplicate::Last => quote! {
+            (|key, val| {
+                #trace
+                #temp.#method(key, val);
+            })(#key, #val);
+        },
+        Duplicate::First => quote! {
+            (|key, val| {
+                if !#temp.contains_key(&key) {
+                    #trace
+                    #temp.#method(key, val);
+                }
+            })(#key, #val);
+        },
+        Duplicate::Panic => quote! {
+            (|key, val| {
+                if #temp.contains_key(&key) {
+                    ::std::panic!("duplicate key `{:?}` in literal", key);
+                }
+                #trace
+                #temp.#method(key, val);
+            })(#key, #val);
+        },
+    }
+}
+
+/// The statement logging what a literal adds through the `#[lit(trace)]` macro at `path`, if any
+///
+/// It's only compiled with `debug_assertions`, and starts with the location of the literal.
+pub(crate) fn trace_stmt(path: Option<&Path>, format: &str, args: TokenStream) -> TokenStream {
+    match path {
+        Some(path) => {
+            let format = format!("{{}}:{{}}: literal added {}", format);
+            quote! {
+                #[cfg(debug_assertions)]
+                #path!(#format, ::std::file!(), ::std::line!(), #args);
+            }
+        }
+        None => quote!(),
+    }
+}
+
+/// The macro arms of the `try_` macro, for plain literals adding each element or entry with the fallible `method`
+///
+/// The literal evaluates to `Ok` with the value, or to the first `Err` returned by `method`, without adding the rest.
+fn try_body(target: &Target, kind: Kind, method: &Ident, options: &Options) -> TokenStream {
+    let add = |args: TokenStream| {
+        quote! {
+            if let ::std::result::Result::Err(err) = temp.#method(#args) {
+                break 'lit ::std::result::Result::Err(err);
+            }
+        }
+    };
+    let (pattern, each, stmts) = match kind {
+        Kind::Map => {
+            let add = add(quote!($key, $val));
+            (
+                quote!($($key:expr => $val:expr),*),
+                quote!($key),
+                quote!($( #add )*),
+            )
+        }
+        _ => {
+            // as in the plain arm, the one-element array lets `#[cfg]` on an element remove it
+            let add = add(quote!(elem));
+            (
+                quote!($( $elem:expr ),*),
+                quote!($elem),
+                quote! {
+                    $(
+                        for elem in [$elem] {
+                            #add
+                        }
+                    )*
+                },
+            )
+        }
+    };
+
+    let forms = [Form {
+        pattern,
+        each: Some(each),
+        build: Box::new(move |new| {
+            // a loop that always breaks, rather than a labeled block, which older compilers don't have
+            quote! {
+                {
+                    #[allow(clippy::never_loop)]
+                    let result = 'lit: loop {
+                        let mut temp = #new;
+                        #stmts
+                        break 'lit ::std::result::Result::Ok(temp);
+                    };
+                    result
+                }
+            }
+        }),
+    }];
+
+    arms(target, options, &ctors(options), &forms)
+}
+
+/// The `TryFrom<Vec<T>>` and `TryFrom<&[T]>` impls given by `#[lit(try_from = "T", capacity = n)]`, and the error type
+/// they fail with on more than `capacity` elements
+fn try_from_impls(
+    input: &DeriveInput,
+    kind: Kind,
+    elem: &Type,
+    capacity: &LitInt,
+    options: &Options,
+) -> Vec<TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let error = format_ident!("{}CapacityError", name.unraw());
+    let error_doc = format!(
+        "The error of converting more than {} elements into a `{}`",
+        capacity,
+        name.unraw()
+    );
+    let method = match kind {
+        Kind::VecFront => quote!(push_front),
+        Kind::Set => quote!(insert),
+        _ => quote!(push),
+    };
+    let new = new_expr(&quote!(Self), options, None);
+    let (elem, add) = ctor_add(&method, elem, options, "try_from");
+    let track_caller = options
+        .track_caller
+        .as_ref()
+        .map(|_| quote!(#[track_caller]));
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    // the slice impl needs a lifetime of its own, and clones the elements
+    let mut slice_generics = input.generics.clone();
+    slice_generics.params.insert(0, syn::parse_quote!('lit));
+    slice_generics
+        .make_where_clause()
+        .predicates
+        .push(syn::parse_quote!(#elem: ::std::clone::Clone));
+    let (slice_impl_generics, _, slice_where_clause) = slice_generics.split_for_impl();
+
+    // with `skip_none`, only the elements that are added count
+    let len = if options.skip_none {
+        quote!(elems.iter().filter(|elem| elem.is_some()).count())
+    } else {
+        quote!(elems.len())
+    };
+    let fill = |iter: TokenStream| {
+        let iter = if options.reverse {
+            quote!(::std::iter::Iterator::rev(#iter))
+        } else {
+            iter
+        };
+        quote! {
+            let len = #len;
+            if len > #capacity {
+                return ::std::result::Result::Err(#error {
+                    len,
+                    capacity: #capacity,
+                });
+            }
+            let mut temp = #new;
+            for elem in #iter {
+                #add
+            }
+            ::std::result::Result::Ok(temp)
+        }
+    };
+    let fill_vec = fill(quote!(::std::iter::IntoIterator::into_iter(elems)));
+    let fill_slice = fill(quote!(elems.iter().cloned()));
+
+    vec![
+        quote! {
+            #[doc = #error_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis struct #error {
+                /// The number of elements that were given
+                pub len: usize,
+                /// The most elements that fit
+                pub capacity: usize,
+            }
+        },
+        quote! {
+            impl ::std::fmt::Display for #error {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    ::std::write!(f, "{} elements don't fit in a capacity of {}", self.len, self.capacity)
+                }
+            }
+        },
+        quote! {
+            impl ::std::error::Error for #error {}
+        },
+        quote! {
+            impl #impl_generics ::std::convert::TryFrom<::std::vec::Vec<#elem>> for #name #ty_generics #where_clause {
+                type Error = #error;
+
+                #track_caller
+                fn try_from(elems: ::std::vec::Vec<#elem>) -> ::std::result::Result<Self, Self::Error> {
+                    #fill_vec
+                }
+            }
+        },
+        quote! {
+            impl #slice_impl_generics ::std::convert::TryFrom<&'lit [#elem]> for #name #ty_generics #slice_where_clause {
+                type Error = #error;
+
+                #track_caller
+                fn try_from(elems: &'lit [#elem]) -> ::std::result::Result<Self, Self::Error> {
+                    #fill_slice
+                }
+            }
+        },
+    ]
+}
+
+/// The `FromStr` impl given by `#[lit(from_str = "...")]`, parsing the elements or `key => val` entries of a literal
+/// from a string, and the error type it fails with
+///
+/// The elements, keys and values are split at `,` and `=>` and then parsed themselves, so they can't contain either.
+fn from_str_impl(
+    input: &DeriveInput,
+    kind: Kind,
+    key: &Type,
+    val: Option<&Type>,
+    options: &Options,
+) -> Vec<TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let error = format_ident!("{}ParseError", name.unraw());
+    let error_doc = format!("The error of parsing a `{}` from a string", name.unraw());
+    let method = match kind {
+        Kind::VecFront => quote!(push_front),
+        Kind::Vec => quote!(push),
+        _ => quote!(insert),
+    };
+    let new = default_call(options, quote!());
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for ty in std::iter::once(key).chain(val) {
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(#ty: ::std::str::FromStr));
+        where_clause
+            .predicates
+            .push(syn::parse_quote!(<#ty as ::std::str::FromStr>::Err: ::std::fmt::Display));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let add = match val {
+        None => quote! {
+            let elem = entry.parse::<#key>().map_err(|err| error(entry, err))?;
+            temp.#method(elem);
+        },
+        Some(val) => quote! {
+            let (key, val) = match entry.find("=>") {
+                ::std::option::Option::Some(i) => (entry[..i].trim(), entry[i + 2..].trim()),
+                ::std::option::Option::None => {
+                    return ::std::result::Result::Err(error(entry, "expected `key => val`"));
+                }
+            };
+            let key = key.parse::<#key>().map_err(|err| error(entry, err))?;
+            let val = val.parse::<#val>().map_err(|err| error(entry, err))?;
+            temp.#method(key, val);
+        },
+    };
+
+    vec![
+        quote! {
+            #[doc = #error_doc]
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            #vis struct #error {
+                /// The element or entry that couldn't be parsed
+                pub entry: ::std::string::String,
+                /// Why it couldn't be parsed
+                pub reason: ::std::string::String,
+            }
+        },
+        quote! {
+            impl ::std::fmt::Display for #error {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    ::std::write!(f, "can't parse `{}`: {}", self.entry, self.reason)
+                }
+            }
+        },
+        quote! {
+            impl ::std::error::Error for #error {}
+        },
+        quote! {
+            impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+                type Err = #error;
+
+                fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                    fn error(entry: &str, reason: impl ::std::fmt::Display) -> #error {
+                        #error {
+                            entry: ::std::string::ToString::to_string(entry),
+                            reason: ::std::string::ToString::to_string(&reason),
+                        }
+                    }
+
+                    let mut temp = Self::#new;
+                    // like a literal, the string may have a trailing `,`
+                    let s = s.trim();
+                    let s = s.strip_suffix(',').unwrap_or(s);
+                    if s.trim().is_empty() {
+                        return ::std::result::Result::Ok(temp);
+                    }
+                    for entry in s.split(',') {
+                        let entry = entry.trim();
+                        #add
+                    }
+                    ::std::result::Result::Ok(temp)
+                }
+            }
+        },
+    ]
+}
+
+/// The `from_elems` or `from_entries` function given by `#[lit(fn = "...")]` in place of the literal macro, for an array
+/// of elements or `(key, val)` pairs
+fn fn_impl(
+    input: &DeriveInput,
+    kind: Kind,
+    key: &Type,
+    val: Option<&Type>,
+    options: &Options,
+) -> TokenStream {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let method = match kind {
+        Kind::VecFront => quote!(push_front),
+        Kind::Vec => quote!(push),
+        _ => quote!(insert),
+    };
+    let new = sized_new(&quote!(Self), options, &quote!(N));
+    let track_caller = options
+        .track_caller
+        .as_ref()
+        .map(|_| quote!(#[track_caller]));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let (func, param, what, add) = match val {
+        None => {
+            let (elem, add) = ctor_add(&method, key, options, "from_elems");
+            (
+                quote!(from_elems),
+                quote!(elems: [#elem; N]),
+                "elements",
+                quote! {
+                    for elem in elems {
+                        #add
+                    }
+                },
+            )
+        }
+        Some(val) => {
+            let insert = insert_entry(
+                &quote!(temp),
+                &method,
+                options.on_duplicate,
+                None,
+                &quote!(key),
+                &quote!(val),
+            );
+            (
+                quote!(from_entries),
+                quote!(entries: [(#key, #val); N]),
+                "`(key, val)` entries",
+                quote! {
+                    for (key, val) in entries {
+                        #insert
+                    }
+                },
+            )
+        }
+    };
+    let doc = format!(
+        "Creates a `{}` from the given {}, in the order they're given",
+        name.unraw(),
+        what
+    );
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #doc]
+            #track_caller
+            #vis fn #func<const N: usize>(#param) -> Self {
+                let mut temp = #new;
+                #add
+                temp
+            }
+        }
+    }
+}
+
+/// The `LitNew` and `LitPush` or `LitInsert` impls given by `#[lit(traits = "...")]`, wrapping the constructor and the
+/// method that the derive would otherwise call
+fn trait_impls(
+    input: &DeriveInput,
+    kind: Kind,
+    key: &Type,
+    val: Option<&Type>,
+    options: &Options,
+) -> Vec<TokenStream> {
+    let name = &input.ident;
+    let new = default_call(options, quote!());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let add = match val {
+        None => {
+            let method = match kind {
+                Kind::VecFront => quote!(push_front),
+                Kind::Set => quote!(insert),
+                _ => quote!(push),
+            };
+            quote! {
+                impl #impl_generics ::derive_lit_core::LitPush<#key> for #name #ty_generics #where_clause {
+                    fn lit_push(&mut self, elem: #key) {
+                        self.#method(elem);
+                    }
+                }
+            }
+        }
+        Some(val) => quote! {
+            impl #impl_generics ::derive_lit_core::LitInsert<#key, #val> for #name #ty_generics #where_clause {
+                fn lit_insert(&mut self, key: #key, val: #val) {
+                    self.insert(key, val);
+                }
+            }
+        },
+    };
+
+    vec![
+        quote! {
+            impl #impl_generics ::derive_lit_core::LitNew for #name #ty_generics #where_clause {
+                fn lit_new() -> Self {
+                    Self::#new
+                }
+            }
+        },
+        add,
+    ]
+}
+
+/// The `from_slice` function given by `#[lit(from_slice = "T")]`, cloning the elements of a slice
+fn from_slice_impl(input: &DeriveInput, kind: Kind, elem: &Type, options: &Options) -> TokenStream {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let method = match kind {
+        Kind::VecFront => quote!(push_front),
+        Kind::Set => quote!(insert),
+        _ => quote!(push),
+    };
+    let new = sized_new(&quote!(Self), options, &quote!(elems.len()));
+    let (elem_ty, add) = ctor_add(&method, elem, options, "from_slice");
+    let track_caller = options
+        .track_caller
+        .as_ref()
+        .map(|_| quote!(#[track_caller]));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let doc = format!(
+        "Creates a `{}` from clones of the elements of a slice, in order",
+        name.unraw()
+    );
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #doc]
+            #track_caller
+            #vis fn from_slice(elems: &[#elem_ty]) -> Self
+            where
+                #elem_ty: ::std::clone::Clone,
+            {
+                let mut temp = #new;
+                for elem in elems {
+                    let elem = ::std::clone::Clone::clone(elem);
+                    #add
+                }
+                temp
+            }
+        }
+    }
+}
+
+/// The `from_pairs` function given by `#[lit(from_pairs = "K => V")]`, inserting the `(key, val)` pairs of an iterator
+/// the way the macro inserts entries
+fn from_pairs_impl(input: &DeriveInput, key: &Type, val: &Type, options: &Options) -> TokenStream {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let new = default_call(options, quote!());
+    let insert = insert_entry(
+        &quote!(temp),
+        &quote!(insert),
+        options.on_duplicate,
+        None,
+        &key_expr(options.key_wrap.as_ref(), options.key_into, quote!(key)),
+        &into_expr(options.value_into, quote!(val)),
+    );
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let doc = format!(
+        "Creates a `{}` from `(key, val)` pairs, inserted in order",
+        name.unraw()
+    );
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #doc]
+            #vis fn from_pairs(pairs: impl ::std::iter::IntoIterator<Item = (#key, #val)>) -> Self {
+                let mut temp = Self::#new;
+                for (key, val) in pairs {
+                    #insert
+                }
+                temp
+            }
+        }
+    }
+}
+
+/// The type of the elements given to the generated function `func`, for the element type `elem`, and the statement
+/// adding each `elem` to `temp` with `method` the way the macro would
+///
+/// With `track_caller`, the function needs `#[track_caller]` for the location to be that of its caller.
+fn ctor_add(
+    method: &TokenStream,
+    elem: &Type,
+    options: &Options,
+    func: &str,
+) -> (TokenStream, TokenStream) {
+    let trace = trace_stmt(options.trace.as_ref(), "{:?}", quote!(&elem));
+    let call = match (&options.traits, &options.track_caller) {
+        (Some(_), _) => quote!(::derive_lit_core::LitPush::lit_push(&mut temp, elem)),
+        (None, Some(track_caller)) => {
+            quote!(temp.#track_caller(elem, ::std::panic::Location::caller()))
+        }
+        (None, None) => quote!(temp.#method(elem)),
+    };
+    let insert = if options.strict {
+        let message = format!("duplicate element in `{}`", func);
+        quote! {
+            #trace
+            if !#call {
+                ::std::panic!(#message);
+            }
+        }
+    } else {
+        quote! {
+            #trace
+            #call;
+        }
+    };
+    if options.skip_none {
+        (
+            quote!(::std::option::Option<#elem>),
+            quote! {
+                if let ::std::option::Option::Some(elem) = elem {
+                    #insert
+                }
+            },
+        )
+    } else {
+        (quote!(#elem), insert)
+    }
+}
+
+/// The `to_lit_string` method given by `#[lit(to_lit_string = "method")]`, rendering what `method` iterates over as a
+/// literal of the macro `macro_name`, with the `Debug` output of each element, key and value
+fn to_lit_string_impl(
+    input: &DeriveInput,
+    kind: Kind,
+    method: &Ident,
+    macro_name: &Ident,
+    options: &Options,
+) -> TokenStream {
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(options.bounds.iter().cloned());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // a map literal is spaced inside its braces, like `my_map! { "a" => 1 }`
+    let (empty, open, close, render) = match kind {
+        Kind::Map => (
+            format!("{}! {{}}", macro_name),
+            format!("{}! {{ ", macro_name),
+            " }",
+            quote! {
+                fn render<K: ::std::fmt::Debug, V: ::std::fmt::Debug>((key, val): (K, V)) -> ::std::string::String {
+                    ::std::format!("{:?} => {:?}", key, val)
+                }
+            },
+        ),
+        _ => (
+            format!("{}![]", macro_name),
+            format!("{}![", macro_name),
+            "]",
+            quote! {
+                fn render<T: ::std::fmt::Debug>(elem: T) -> ::std::string::String {
+                    ::std::format!("{:?}", elem)
+                }
+            },
+        ),
+    };
+
+    // each element of a vec-front literal is pushed in front of the ones before it, so they're written in reverse
+    let reverse = match kind {
+        Kind::VecFront => quote! {
+            let mut entries = entries;
+            entries.reverse();
+        },
+        _ => quote!(),
+    };
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Renders the data structure as a literal of its macro, which can be pasted back into code
+            #[allow(dead_code)]
+            pub fn to_lit_string(&self) -> ::std::string::String {
+                #render
+
+                let entries: ::std::vec::Vec<_> = self.#method().map(render).collect();
+                #reverse
+                if entries.is_empty() {
+                    ::std::string::ToString::to_string(#empty)
+                } else {
+                    ::std::format!("{}{}{}", #open, entries.join(", "), #close)
+                }
+            }
+        }
+    }
+}
+
+/// The arms of the `assert_..._eq` macro given by `#[lit(assert_eq = "method")]`, which compares what `method`
+/// iterates over for the actual value and for a literal of `macro_name`
+///
+/// Vec-like data structures are compared in order, while sets and maps are compared regardless of order, and the panic
+/// points out the first element or key that differs.
+fn assert_body(
+    macro_name: &Ident,
+    macro_path: &TokenStream,
+    kind: Kind,
+    method: &Ident,
+) -> TokenStream {
+    let compare = match kind {
+        Kind::Vec | Kind::VecFront => quote! {
+            let mut left = actual.#method();
+            let mut right = expected.#method();
+            let mut i = 0usize;
+            loop {
+                match (left.next(), right.next()) {
+                    (::std::option::Option::None, ::std::option::Option::None) => break,
+                    (left, right) if left != right => ::std::panic!(
+                        "assertion `{} == {}` failed at element {}\n  left: {:?}\n right: {:?}",
+                        what, literal, i, left, right
+                    ),
+                    _ => i += 1,
+                }
+            }
+        },
+        Kind::Set => quote! {
+            let left: ::std::vec::Vec<_> = actual.#method().collect();
+            let right: ::std::vec::Vec<_> = expected.#method().collect();
+            for (elems, others, side) in [(&left, &right, "left"), (&right, &left, "right")] {
+                if let ::std::option::Option::Some(elem) = elems.iter().find(|elem| !others.contains(elem)) {
+                    ::std::panic!(
+                        "assertion `{} == {}` failed\n{:?} is only in the {}",
+                        what, literal, elem, side
+                    );
+                }
+            }
+        },
+        _ => quote! {
+            let left: ::std::vec::Vec<_> = actual.#method().collect();
+            let right: ::std::vec::Vec<_> = expected.#method().collect();
+            for (key, val) in &left {
+                match right.iter().find(|(other, _)| other == key) {
+                    ::std::option::Option::None => ::std::panic!(
+                        "assertion `{} == {}` failed\nkey {:?} is only in the left",
+                        what, literal, key
+                    ),
+                    ::std::option::Option::Some((_, other)) if other != val => ::std::panic!(
+                        "assertion `{} == {}` failed at key {:?}\n  left: {:?}\n right: {:?}",
+                        what, literal, key, val, other
+                    ),
+                    _ => {}
+                }
+            }
+            if let ::std::option::Option::Some((key, _)) =
+                right.iter().find(|(key, _)| !left.iter().any(|(other, _)| other == key))
+            {
+                ::std::panic!(
+                    "assertion `{} == {}` failed\nkey {:?} is only in the right",
+                    what, literal, key
+                );
+            }
+        },
+    };
+
+    let arm = |pattern: TokenStream, literal: TokenStream| {
+        quote! {
+            ($actual:expr, #pattern $(,)?) => {{
+                let actual = &$actual;
+                let expected = #macro_path! #literal;
+                let what = stringify!($actual);
+                let literal = concat!(stringify!(#macro_name), "!", stringify!(#literal));
+                #compare
+            }};
+        }
+    };
+    let elems = arm(quote!([$($lit:tt)*]), quote!([$($lit)*]));
+    let entries = arm(quote!({$($lit:tt)*}), quote!({$($lit)*}));
+    quote! {
+        #elems
+        #entries
+    }
+}
+
+/// The macro arms for literals that are a list of `(row, col) => val` entries
+pub(crate) fn triplet_body(
+    target: &Target,
+    method: &TokenStream,
+    options: &Options,
+) -> TokenStream {
+    let mut forms = vec![];
+    if let Some(set_block) = &options.blocks {
+        // the rows are checked at compile time to have as many blocks each, and the last of the increments is unused
+        let len = quote!(<[&str]>::len(&[$(stringify!($block)),+]));
+        forms.push(Form::new(
+            quote!([ $( $($block:expr),+ );+ $(;)? ]),
+            None,
+            quote! {
+                const _: () = {
+                    let lens = [$( #len ),+];
+                    let mut row = 1;
+                    while row < lens.len() {
+                        ::std::assert!(
+                            lens[row] == lens[0],
+                            "every row of blocks in a literal must have the same number of blocks"
+                        );
+                        row += 1;
+                    }
+                };
+                #[allow(unused_assignments)]
+                {
+                    let mut row = 0usize;
+                    $(
+                        let mut col = 0usize;
+                        $(
+                            temp.#set_block(row, col, $block);
+                            col += 1;
+                        )+
+                        row += 1;
+                    )+
+                }
+            },
+        ));
+    }
+    forms.push(Form::new(
+        quote!($( ($row:expr, $col:expr) => $val:expr ),*),
+        Some(quote!($row)),
+        quote! {
+            $(
+                temp.#method($row, $col, $val);
+            )*
+        },
+    ));
+
+    arms(target, options, &ctors(options), &forms)
+}
+
+/// The macro arms for histogram literals that are a list of `bucket => count` entries
+pub(crate) fn histogram_body(target: &Target, options: &Options) -> TokenStream {
+    let mut forms = vec![];
+    if options.contiguous {
+        // buckets written with literal bounds are checked at compile time, others fall through unchecked
+        forms.push(Form::new(
+            quote!($( $lo:literal .. $hi:literal => $count:expr ),*),
+            Some(quote!($count)),
+            quote! {
+                ::derive_lit::__check_buckets!($( $lo .. $hi ),*);
+                $(
+                    temp.record($lo..$hi, $count);
+                )*
+            },
+        ));
+    }
+    forms.push(map_form(
+        &quote!(record),
+        Duplicate::Last,
+        options.trace.as_ref(),
+        &quote!($key),
+        &quote!($val),
+    ));
+
+    arms(target, options, &ctors(options), &forms)
+}
+
+/// The macro arms for bounded cache literals, a capacity followed by a list of `key => val` entries
+pub(crate) fn cache_body(target: &Target, method: &TokenStream, options: &Options) -> TokenStream {
+    let cache_ctors = [Ctor {
+        head: quote!($cap:expr;),
+        new: New::Call(quote!(with_capacity($cap))),
+    }];
+
+    arms(
+        target,
+        options,
+        &cache_ctors,
+        &[map_form(
+            method,
+            Duplicate::Last,
+            options.trace.as_ref(),
+            &quote!($key),
+            &quote!($val),
+        )],
+    )
+}
+
+/// The macro arms for segment tree literals, a list of leaves that update a tree of their length in order
+pub(crate) fn segment_tree_body(target: &Target, options: &Options) -> TokenStream {
+    let len = quote!(<[&str]>::len(&[$(stringify!($leaf)),*]));
+    // with `const_len`, the length is also the tree's const parameter
+    let new = if options.const_len {
+        quote!(<{ #len }>::new(#len))
+    } else {
+        quote!(new(#len))
+    };
+    let tree_ctors = [Ctor {
+        head: quote!(),
+        new: New::Call(new),
+    }];
+
+    arms(
+        target,
+        options,
+        &tree_ctors,
+        &[Form::new(
+            quote!($($leaf:expr),* $(,)?),
+            None,
+            quote! {
+                let leaves = [$($leaf),*];
+                for (index, leaf) in ::std::iter::IntoIterator::into_iter(leaves).enumerate() {
+                    temp.update(index, leaf);
+                }
+            },
+        )],
+    )
+}
+
+/// The macro arms for struct-of-arrays literals, a list of `(...)` rows whose fields are each pushed to their column
+/// with the method at their position in `#[lit(columns = "...")]`
+pub(crate) fn soa_body(target: &Target, options: &Options) -> TokenStream {
+    let columns = &options.columns;
+    let fields: Vec<_> = (0..columns.len())
+        .map(|i| format_ident!("field{}", i))
+        .collect();
+
+    let forms = [
+        // the items of the iterator are tuples like the rows, including for a single column
+        Form::new(
+            quote!(from $iter:expr),
+            None,
+            quote! {
+                for (#(#fields,)*) in $iter {
+                    #( temp.#columns(#fields); )*
+                }
+            },
+        ),
+        Form::new(
+            quote!($( ( #($#fields:expr),* $(,)? ) ),* $(,)?),
+            None,
+            quote! {
+                $(
+                    #( temp.#columns($#fields); )*
+                )*
+            },
+        ),
+    ];
+
+    arms(target, options, &ctors(options), &forms)
+}
+
+/// The macro arms for ECS world literals, a list of `[...]` entities that are each spawned and then given their
+/// components in order
+pub(crate) fn world_body(target: &Target, options: &Options) -> TokenStream {
+    arms(
+        target,
+        options,
+        &ctors(options),
+        &[Form::new(
+            quote!($( [ $($component:expr),* $(,)? ] ),* $(,)?),
+            None,
+            quote! {
+                $(
+                    // an entity without components is still spawned
+                    #[allow(unused_variables)]
+                    let entity = temp.spawn();
+                    $(
+                        temp.insert(::std::clone::Clone::clone(&entity), $component);
+                    )*
+                )*
+            },
+        )],
+    )
+}
+
+/// The macro arms for union-find literals, a list of `{...}` groups whose elements are each made a set and then joined
+/// into one
+pub(crate) fn union_find_body(target: &Target, options: &Options) -> TokenStream {
+    let clone = |elem: TokenStream| quote!(::std::clone::Clone::clone(#elem));
+    let (elem, first, second) = (
+        clone(quote!(elem)),
+        clone(quote!(&pair[0])),
+        clone(quote!(&pair[1])),
+    );
+
+    arms(
+        target,
+        options,
+        &ctors(options),
+        &[Form::new(
+            quote!($( { $($elem:expr),+ $(,)? } ),* $(,)?),
+            None,
+            quote! {
+                $(
+                    let group = [$($elem),+];
+                    for elem in &group {
+                        temp.make_set(#elem);
+                    }
+                    for pair in group.windows(2) {
+                        temp.union(#first, #second);
+                    }
+                )*
+            },
+        )],
+    )
+}
+
+/// The macro arms for binary tree literals, a root written as `(left, value, right)` or `value { left, right }` with `_`
+/// for empty subtrees
+///
+/// The nesting is expanded by `__binary_tree!` into calls of `node`, with the constructor making each empty subtree.
+pub(crate) fn binary_tree_body(target: &Target, options: &Options) -> TokenStream {
+    let path = target.path.clone();
+    let tree_ctors = [Ctor {
+        head: quote!(),
+        new: New::Default,
+    }];
+    let forms = [Form {
+        pattern: quote!($($tree:tt)*),
+        each: None,
+        build: Box::new(move |new| {
+            quote! {
+                ::derive_lit::__binary_tree!({ #new } { #path::node } $($tree)*)
+            }
+        }),
+    }];
+
+    arms(target, options, &tree_ctors, &forms)
+}
+
+/// The macro arms for literals that are a list of `version => val` entries
+///
+/// Keys that are literals are checked by `__version!` to be semver versions, and converted if `#[lit(version)]` is
+/// given, while others fall through as they are.
+pub(crate) fn version_map_body(
+    target: &Target,
+    method: &TokenStream,
+    options: &Options,
+) -> TokenStream {
+    let (on_duplicate, trace) = (options.on_duplicate, options.trace.as_ref());
+    let ctor = options.version.as_ref().map(|ctor| quote!(#ctor;));
+    let key = quote!(::derive_lit::__version!(#ctor $key));
+    let insert = insert_entry(
+        &quote!(temp),
+        method,
+        on_duplicate,
+        trace,
+        &key,
+        &quote!($val),
+    );
+
+    let forms = [
+        Form::new(
+            quote!($( $key:literal => $val:expr ),*),
+            Some(quote!($key)),
+            quote! {
+                $(
+                    #insert
+                )*
+            },
+        ),
+        map_form(method, on_duplicate, trace, &quote!($key), &quote!($val)),
+    ];
+
+    arms(target, options, &ctors(options), &forms)
+}
+
+/// The macro arms for literals that are a list of `elem: weight` entries
+///
+/// An `expr` fragment can't be followed by `:` so the entries are parsed by `__weighted!` instead.
+pub(crate) fn weighted_body(
+    target: &Target,
+    method: &TokenStream,
+    options: &Options,
+) -> TokenStream {
+    let sum = options.weight_sum.as_ref().map(|sum| quote!(, #sum));
+    let method = method.clone();
+
+    let forms = [Form {
+        pattern: quote!($($entries:tt)*),
+        each: None,
+        build: Box::new(move |new| {
+            quote! {
+                ::derive_lit::__weighted!({ #new } #method #sum; $($entries)*)
+            }
+        }),
+    }];
+
+    arms(target, options, &ctors(options), &forms)
+}
+
+/// The macro arms for directory tree literals, `name => contents` files and `name => { ... }` directories
+///
+/// The nested braces are flattened into full paths by `__dir_tree!`.
+pub(crate) fn dir_tree_body(target: &Target, options: &Options) -> TokenStream {
+    let forms = [Form {
+        pattern: quote!($($nodes:tt)*),
+        each: None,
+        build: Box::new(|new| {
+            quote! {
+                ::derive_lit::__dir_tree!({ #new } $($nodes)*)
+            }
+        }),
+    }];
+
+    arms(target, options, &ctors(options), &forms)
+}
+
+/// The macro arms for channel literals, a list of elements sent in order, which evaluate to the channel's handles
+pub(crate) fn channel_body(target: &Target, options: &Options) -> TokenStream {
+    let sender = if options.sender {
+        quote!(temp)
+    } else {
+        quote!(temp.0)
+    };
+    let send = quote! {
+        if #sender.send(elem).is_err() {
+            ::std::panic!("the receiver of a channel literal is disconnected");
+        }
+    };
+
+    let forms = [
+        Form::new(
+            quote!(from $iter:expr),
+            None,
+            quote! {
+                for elem in $iter {
+                    #send
+                }
+            },
+        ),
+        // as in vec-like literals, the one-element array lets `#[cfg]` on an element remove it
+        Form::new(
+            quote!($( $elem:expr ),*),
+            Some(quote!($elem)),
+            quote! {
+                $(
+                    for elem in [$elem] {
+                        #send
+                    }
+                )*
+            },
+        ),
+    ];
+
+    arms(target, options, &ctors(options), &forms)
+}
+
+/// The macro arms for literals made of labeled sections, each added to through its own method
+///
+/// A section is either a list of elements in `[...]` or a list of `key => val` entries in `{...}`.
+fn sections_body(target: &Target, macro_path: &TokenStream, options: &Options) -> TokenStream {
+    // the sections are munched one at a time by internal `@sections` arms so they can be written in any order
+    let mut internal = TokenStream::new();
+    for (label, method) in &options.sections {
+        internal.extend(quote! {
+            (@sections $temp:ident #label: [ $($elem:expr),* ] $(, $($rest:tt)*)?) => {
+                $(
+                    $temp.#method($elem);
+                )*
+                #macro_path!(@sections $temp $($($rest)*)?);
+            };
+            (@sections $temp:ident #label: { $($key:expr => $val:expr),* } $(, $($rest:tt)*)?) => {
+                $(
+                    $temp.#method($key, $val);
+                )*
+                #macro_path!(@sections $temp $($($rest)*)?);
+            };
+        });
+    }
+    internal.extend(quote! {
+        (@sections $temp:ident) => {};
+    });
+
+    let macro_path = macro_path.clone();
+    let forms = [Form {
+        pattern: quote!($($sections:tt)*),
+        each: None,
+        build: Box::new(move |new| {
+            quote! {
+                {
+                    let mut temp = #new;
+                    #macro_path!(@sections temp $($sections)*);
+                    temp
+                }
+            }
+        }),
+    }];
+    let arms = arms(target, options, &ctors(options), &forms);
+
+    quote! {
+        #internal
+        #arms
+    }
+}
+
+/// The expression constructing an empty value inside a macro arm that repeats over `each`
+fn new_expr(path: &TokenStream, options: &Options, each: Option<&TokenStream>) -> TokenStream {
+    let args = if options.new_args {
+        quote!($($arg),*)
+    } else {
+        quote!()
+    };
+
+    match (&options.inline_capacity, each) {
+        _ if options.traits.is_some() => lit_new(path),
+        (Some(_), Some(each)) => {
+            // `__count!` keeps the elements themselves from being evaluated twice, and leaves out those that `#[cfg]`
+            // removes
+            let count = quote!(::derive_lit::__count!($(#each),*));
+            sized_new(path, options, &count)
+        }
+        _ => {
+            let new = default_call(options, args);
+            quote!(#path::#new)
+        }
+    }
+}
+
+/// The call of the default constructor with `args`, following `#[lit(ctor(...))]`, `#[lit(default)]` and
+/// `#[lit(hasher = "...")]`
+fn default_call(options: &Options, args: TokenStream) -> TokenStream {
+    match (&options.hasher, &options.default_ctor) {
+        (Some(hasher), _) => quote!(with_hasher(#hasher())),
+        (None, Some(method)) => quote!(#method(#args)),
+        (None, None) => quote!(new(#args)),
+    }
+}
+
+/// The construction of `path` through `LitNew`, for `#[lit(traits)]`
+fn lit_new(path: &TokenStream) -> TokenStream {
+    quote!(<#path as ::derive_lit_core::LitNew>::lit_new())
+}
+
+/// The construction of `path` for `count` elements, which only preallocates for more than the
+/// `#[lit(inline_capacity = n)]` elements that fit without allocating
+fn sized_new(path: &TokenStream, options: &Options, count: &TokenStream) -> TokenStream {
+    let new = default_call(options, quote!());
+    match &options.inline_capacity {
+        _ if options.traits.is_some() => lit_new(path),
+        Some(capacity) => {
+            let with_capacity = capacity_call(options, count);
+            quote! {
+                if #count <= #capacity {
+                    #path::#new
+                } else {
+                    #path::#with_capacity
+                }
+            }
+        }
+        None => quote!(#path::#new),
+    }
+}
+
+/// The call of the constructor with a capacity of `cap`, which also takes the hasher given by `#[lit(hasher = "...")]`
+fn capacity_call(options: &Options, cap: &TokenStream) -> TokenStream {
+    match &options.hasher {
+        Some(hasher) => quote!(with_capacity_and_hasher(#cap, #hasher())),
+        None => quote!(with_capacity(#cap)),
+    }
+}
+
+/// Wraps each generated item in the `#[cfg]` given by `#[lit(cfg = "...")]`, if any
+fn gate(options: &Options, items: Vec<TokenStream>) -> TokenStream {
+    match &options.cfg {
+        Some(cfg) => quote! { #( #[cfg(#cfg)] #items )* },
+        None => quote! { #( #items )* },
+    }
+}
@@ -0,0 +1,278 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Expr, LitBool, LitStr, Path, Result};
+
+/// Parses `json`, the contents of the file at `path`, as an object whose members become `key => val` entries
+///
+/// Values are expanded to the Rust literals they're written like, arrays to arrays, and each is passed to `convert` if
+/// it's given. Nested objects and `null`s have no literal, so they're rejected.
+pub(crate) fn entries(
+    json: &str,
+    path: &LitStr,
+    convert: Option<&Path>,
+) -> Result<Vec<(LitStr, Expr)>> {
+    let span = path.span();
+    let error = |parser: &Parser, reason: &str| {
+        syn::Error::new(
+            span,
+            format!(
+                "invalid JSON in `{}` on line {}: {}",
+                path.value(),
+                parser.line(),
+                reason
+            ),
+        )
+    };
+
+    let mut parser = Parser { json, pos: 0 };
+    let members = match parser.value() {
+        Ok(Value::Object(members)) => members,
+        Ok(_) => return Err(error(&parser, "expected an object")),
+        Err(reason) => return Err(error(&parser, &reason)),
+    };
+    parser.skip_whitespace();
+    if parser.pos < json.len() {
+        return Err(error(&parser, "unexpected text after the object"));
+    }
+
+    members
+        .into_iter()
+        .map(|(key, val)| {
+            let val = val.tokens(span).map_err(|reason| {
+                syn::Error::new(
+                    span,
+                    format!("can't insert `{}` of `{}`: {}", key, path.value(), reason),
+                )
+            })?;
+            let val = match convert {
+                Some(convert) => quote!(#convert(#val)),
+                None => val,
+            };
+            Ok((LitStr::new(&key, span), syn::parse2(val)?))
+        })
+        .collect()
+}
+
+/// A JSON value, with numbers kept as they're written
+enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// The Rust expression written like the value
+    fn tokens(&self, span: Span) -> std::result::Result<TokenStream, String> {
+        match self {
+            Value::Null => Err("`null` has no literal".to_string()),
+            Value::Bool(b) => {
+                let b = LitBool::new(*b, span);
+                Ok(quote!(#b))
+            }
+            // JSON numbers are also valid Rust literals, possibly negated
+            Value::Number(n) => n.parse().map_err(|_| format!("`{}` has no literal", n)),
+            Value::String(s) => {
+                let s = LitStr::new(s, span);
+                Ok(quote!(#s))
+            }
+            Value::Array(vals) => {
+                let vals = vals
+                    .iter()
+                    .map(|val| val.tokens(span))
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                Ok(quote!([#(#vals),*]))
+            }
+            Value::Object(_) => Err("nested objects have no literal".to_string()),
+        }
+    }
+}
+
+struct Parser<'a> {
+    json: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// The line of the current position, for errors
+    fn line(&self) -> usize {
+        self.json[..self.pos].matches('\n').count() + 1
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.json[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> std::result::Result<(), String> {
+        self.skip_whitespace();
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected `{}`, found `{}`", expected, c)),
+            None => Err(format!("expected `{}`, found the end", expected)),
+        }
+    }
+
+    fn value(&mut self) -> std::result::Result<Value, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                let mut members = vec![];
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.pos += 1;
+                    return Ok(Value::Object(members));
+                }
+                loop {
+                    self.expect('"')?;
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    members.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.next() {
+                        Some(',') => {}
+                        Some('}') => return Ok(Value::Object(members)),
+                        _ => return Err("expected `,` or `}`".to_string()),
+                    }
+                }
+            }
+            Some('[') => {
+                self.pos += 1;
+                let mut vals = vec![];
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.pos += 1;
+                    return Ok(Value::Array(vals));
+                }
+                loop {
+                    vals.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.next() {
+                        Some(',') => {}
+                        Some(']') => return Ok(Value::Array(vals)),
+                        _ => return Err("expected `,` or `]`".to_string()),
+                    }
+                }
+            }
+            Some('"') => {
+                self.pos += 1;
+                self.string().map(Value::String)
+            }
+            Some('-' | '0'..='9') => self.number(),
+            _ => {
+                for (word, val) in [
+                    ("true", Value::Bool(true)),
+                    ("false", Value::Bool(false)),
+                    ("null", Value::Null),
+                ] {
+                    if self.json[self.pos..].starts_with(word) {
+                        self.pos += word.len();
+                        return Ok(val);
+                    }
+                }
+                Err("expected a value".to_string())
+            }
+        }
+    }
+
+    /// Parses the rest of a string, after its opening quote
+    fn string(&mut self) -> std::result::Result<String, String> {
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.next() {
+                    Some('"') => s.push('"'),
+                    Some('\\') => s.push('\\'),
+                    Some('/') => s.push('/'),
+                    Some('b') => s.push('\u{8}'),
+                    Some('f') => s.push('\u{c}'),
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some('u') => {
+                        let high = self.hex()?;
+                        let c = if (0xd800..0xdc00).contains(&high) {
+                            // a surrogate pair, written as two escapes
+                            if !self.json[self.pos..].starts_with("\\u") {
+                                return Err("unpaired surrogate".to_string());
+                            }
+                            self.pos += 2;
+                            let low = self.hex()?;
+                            if !(0xdc00..0xe000).contains(&low) {
+                                return Err("invalid unicode escape".to_string());
+                            }
+                            char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+                        } else {
+                            // a lone low surrogate isn't a `char`, so `from_u32` rejects it
+                            char::from_u32(high)
+                        };
+                        s.push(c.ok_or("invalid unicode escape")?);
+                    }
+                    _ => return Err("invalid escape".to_string()),
+                },
+                Some(c) if c < ' ' => return Err("unescaped control character".to_string()),
+                Some(c) => s.push(c),
+                None => return Err("unclosed string".to_string()),
+            }
+        }
+    }
+
+    fn hex(&mut self) -> std::result::Result<u32, String> {
+        let digits = self.json.get(self.pos..self.pos + 4).unwrap_or_default();
+        let n = u32::from_str_radix(digits, 16).map_err(|_| "invalid unicode escape")?;
+        self.pos += 4;
+        Ok(n)
+    }
+
+    fn number(&mut self) -> std::result::Result<Value, String> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        let digits = |parser: &mut Self| {
+            let start = parser.pos;
+            while matches!(parser.peek(), Some('0'..='9')) {
+                parser.pos += 1;
+            }
+            parser.pos > start
+        };
+        let int = self.pos;
+        if !digits(self) {
+            return Err("expected a digit".to_string());
+        }
+        if self.json[int..self.pos].len() > 1 && self.json[int..].starts_with('0') {
+            return Err("number with a leading zero".to_string());
+        }
+        if self.peek() == Some('.') {
+            self.pos += 1;
+            if !digits(self) {
+                return Err("expected a digit after `.`".to_string());
+            }
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.pos += 1;
+            }
+            if !digits(self) {
+                return Err("expected a digit in the exponent".to_string());
+            }
+        }
+        Ok(Value::Number(self.json[start..self.pos].to_string()))
+    }
+}
@@ -0,0 +1,91 @@
+//! The code generation behind [`derive_lit`](https://docs.rs/derive_lit), as a library that build scripts and other
+//! procedural macros can call to generate literal macros for their own types.
+//!
+//! The generated macros expand to invocations of the hidden macros of `derive_lit`, like `::derive_lit::__elems!`, so
+//! the crate they end up in still needs to depend on `derive_lit`, even when they're written out by a build script.
+//!
+//! [`generate`] gives the literal macro for a type, the same one that its `derive_lit` derive would generate, and
+//! [`derive`] gives everything the derive expands to.
+//!
+//! Types whose derive has `#[lit(traits = "...")]` implement [`LitNew`] and [`LitPush`] or [`LitInsert`], which their
+//! literals construct and add through, and which generic code can build any of them through.
+//! ```
+//! use derive_lit_core::{generate, macro_ident, Kind, Options};
+//! use syn::{parse_quote, Ident};
+//!
+//! let name: Ident = parse_quote!(GroceryList);
+//! let options = Options::default();
+//! assert_eq!(macro_ident(&name, &options).unwrap(), "grocery_list");
+//!
+//! let tokens = generate(&name, &Default::default(), Kind::Vec, &options);
+//! assert!(tokens.to_string().starts_with("macro_rules ! grocery_list"));
+//! ```
+//!
+//! With the `std_impls` feature, the collections of `std` implement the traits too, so generic code takes those just as
+//! well.
+//! ```
+//! # #[cfg(feature = "std_impls")] {
+//! use derive_lit_core::{LitNew, LitPush};
+//! use std::collections::{BTreeSet, VecDeque};
+//!
+//! fn first_ids<T: LitNew + LitPush<usize>>(n: usize) -> T {
+//!     let mut ids = T::lit_new();
+//!     for id in 0..n {
+//!         ids.lit_push(id);
+//!     }
+//!     ids
+//! }
+//!
+//! let x: VecDeque<usize> = first_ids(3);
+//! let y: BTreeSet<usize> = first_ids(3);
+//! assert!(x.into_iter().eq(y));
+//! # }
+//! ```
+
+mod alias;
+mod attr;
+mod batch;
+mod binary_tree;
+mod check;
+mod count;
+mod csv;
+mod elems;
+mod entries;
+mod expand;
+mod json;
+mod sorted;
+mod std_lits;
+mod summary;
+mod traits;
+mod tree;
+mod version;
+mod weighted;
+
+pub use alias::Alias;
+pub use attr::{Duplicate, Options};
+pub use batch::Batch;
+pub use expand::{derive, derive_any, derive_enum, generate, macro_ident, Kind};
+pub use std_lits::StdLit;
+pub use summary::macro_arms;
+pub use traits::{LitInsert, LitNew, LitPush};
+
+#[doc(hidden)]
+pub use binary_tree::BinaryTree;
+#[doc(hidden)]
+pub use check::Buckets;
+#[doc(hidden)]
+pub use count::Count;
+#[doc(hidden)]
+pub use csv::Csv;
+#[doc(hidden)]
+pub use elems::Elems;
+#[doc(hidden)]
+pub use entries::Entries;
+#[doc(hidden)]
+pub use sorted::Sorted;
+#[doc(hidden)]
+pub use tree::DirTree;
+#[doc(hidden)]
+pub use version::VersionKey;
+#[doc(hidden)]
+pub use weighted::Weighted;
@@ -0,0 +1,105 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, bracketed, Expr, ExprLit, Ident, Lit, Path, Result, Token};
+
+use crate::{check, expand};
+
+/// The input to `__sorted!`, e.g. `{ MyMap::from_sorted_iter } [own_keys key_into key_wrap = Key value_into]; "b" => 2, "a" => 1`,
+/// with the flags in `[...]` being optional
+///
+/// The elements of a set-like literal are written without `=> val`.
+pub struct Sorted {
+    bulk: TokenStream,
+    own_keys: bool,
+    key_into: bool,
+    key_wrap: Option<Path>,
+    value_into: bool,
+    /// The literal keys or elements, sorted, and the values of map entries
+    entries: Vec<(Expr, Option<Expr>)>,
+}
+
+impl Parse for Sorted {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let bulk;
+        braced!(bulk in input);
+        let bulk = bulk.parse()?;
+
+        let mut own_keys = false;
+        let mut key_into = false;
+        let mut key_wrap = None;
+        let mut value_into = false;
+        if input.peek(syn::token::Bracket) {
+            let flags;
+            bracketed!(flags in input);
+            while !flags.is_empty() {
+                let flag: Ident = flags.parse()?;
+                match flag.to_string().as_str() {
+                    "own_keys" => own_keys = true,
+                    "key_into" => key_into = true,
+                    "key_wrap" => {
+                        flags.parse::<Token![=]>()?;
+                        key_wrap = Some(flags.parse()?);
+                    }
+                    "value_into" => value_into = true,
+                    _ => return Err(syn::Error::new(flag.span(), "unknown flag")),
+                }
+            }
+        }
+        input.parse::<Token![;]>()?;
+
+        let mut entries = vec![];
+        while !input.is_empty() {
+            let key = input.parse()?;
+            let val = if input.parse::<Option<Token![=>]>>()?.is_some() {
+                Some(input.parse()?)
+            } else {
+                None
+            };
+            entries.push((key, val));
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        let what = if entries.iter().any(|(_, val)| val.is_some()) {
+            "key"
+        } else {
+            "element"
+        };
+        let entries = check::sort_distinct(entries, |(key, _)| key, what)?;
+
+        Ok(Sorted {
+            bulk,
+            own_keys,
+            key_into,
+            key_wrap,
+            value_into,
+            entries,
+        })
+    }
+}
+
+impl Sorted {
+    pub fn expand(&self) -> TokenStream {
+        let bulk = &self.bulk;
+        let items = self.entries.iter().map(|(key, val)| {
+            let key = match check::ungroup(key) {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(_), ..
+                }) if self.own_keys => quote!(::std::borrow::ToOwned::to_owned(#key)),
+                _ => quote!(#key),
+            };
+            let key = expand::key_expr(self.key_wrap.as_ref(), self.key_into, key);
+            match val {
+                Some(val) => {
+                    let val = expand::into_expr(self.value_into, quote!(#val));
+                    quote!((#key, #val))
+                }
+                None => key,
+            }
+        });
+
+        quote!(#bulk([#(#items),*]))
+    }
+}
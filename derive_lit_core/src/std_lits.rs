@@ -0,0 +1,53 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::Ident;
+
+use crate::attr::Options;
+use crate::expand::{self, Target};
+
+/// A collection of `std` with a ready-made literal macro, like `hash_map!`
+#[derive(Clone, Copy)]
+pub enum StdLit {
+    HashMap,
+    BTreeMap,
+    HashSet,
+    BTreeSet,
+    VecDeque,
+}
+
+impl StdLit {
+    /// Expands the ready-made macro's invocation with `input`
+    ///
+    /// The literal macro is generated like a derive's, and then defined and invoked in a block of its own.
+    pub fn expand(self, input: TokenStream) -> TokenStream {
+        let (ty, is_map, method) = match self {
+            StdLit::HashMap => (quote!(HashMap<_, _>), true, quote!(insert)),
+            StdLit::BTreeMap => (quote!(BTreeMap<_, _>), true, quote!(insert)),
+            StdLit::HashSet => (quote!(HashSet<_>), false, quote!(insert)),
+            StdLit::BTreeSet => (quote!(BTreeSet<_>), false, quote!(insert)),
+            StdLit::VecDeque => (quote!(VecDeque<_>), false, quote!(push_back)),
+        };
+        let target = Target {
+            path: quote!(<::std::collections::#ty>),
+            generic: None,
+            wrap: None,
+            extend: false,
+        };
+        let options = Options::default();
+        let body = if is_map {
+            expand::map_body(&target, &method, &options, true)
+        } else {
+            expand::elem_body(&target, &method, &options)
+        };
+
+        let name = Ident::new("lit", Span::mixed_site());
+        quote! {
+            {
+                macro_rules! #name {
+                    #body
+                }
+                #name!(#input)
+            }
+        }
+    }
+}
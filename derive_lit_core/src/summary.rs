@@ -0,0 +1,117 @@
+use proc_macro2::{Delimiter, Ident, Spacing, TokenStream, TokenTree};
+
+/// The macros that `tokens` defines with `macro_rules!`, each with the patterns of its arms, for describing what a
+/// derive generated
+/// ```
+/// use derive_lit_core::{generate, macro_arms, Kind, Options};
+/// use syn::{parse_quote, Ident};
+///
+/// let name: Ident = parse_quote!(GroceryList);
+/// let tokens = generate(&name, &Default::default(), Kind::Vec, &Options::default());
+///
+/// let (macro_name, arms) = &macro_arms(&tokens)[0];
+/// assert_eq!(macro_name, "grocery_list");
+/// assert!(arms.contains(&"(cap = $cap:expr; $($elems:tt)*)".to_string()));
+/// assert!(arms.contains(&"(from $iter:expr)".to_string()));
+/// ```
+pub fn macro_arms(tokens: &TokenStream) -> Vec<(Ident, Vec<String>)> {
+    let mut macros = vec![];
+    find_macros(tokens.clone(), &mut macros);
+    macros
+}
+
+fn find_macros(tokens: TokenStream, macros: &mut Vec<(Ident, Vec<String>)>) {
+    let tokens: Vec<_> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        match (token, tokens.get(i + 2), tokens.get(i + 3)) {
+            (
+                TokenTree::Ident(keyword),
+                Some(TokenTree::Ident(name)),
+                Some(TokenTree::Group(body)),
+            ) if keyword == "macro_rules" => {
+                macros.push((name.clone(), arms(body.stream())));
+            }
+            (TokenTree::Group(group), _, _) => find_macros(group.stream(), macros),
+            _ => {}
+        }
+    }
+}
+
+/// The patterns of the arms in the body of a `macro_rules!`, each written as `(pattern) => { ... };`
+fn arms(body: TokenStream) -> Vec<String> {
+    let tokens: Vec<_> = body.into_iter().collect();
+    tokens
+        .windows(3)
+        .filter_map(|window| match window {
+            [TokenTree::Group(pattern), TokenTree::Punct(eq), TokenTree::Punct(gt)]
+                if eq.as_char() == '=' && gt.as_char() == '>' =>
+            {
+                let mut arm = String::new();
+                write_group(pattern.delimiter(), pattern.stream(), &mut arm);
+                Some(arm)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Writes tokens the way they'd be written by hand, like `$($key:expr => $val:expr),*`, rather than with a space
+/// between every token
+fn write_tokens(tokens: TokenStream, out: &mut String) {
+    // whether the last token was a word, which needs a space before another word, and whether the next token gets a
+    // space after a separator
+    let mut after_word = false;
+    let mut space = false;
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(_) | TokenTree::Literal(_) => {
+                if after_word || space {
+                    out.push(' ');
+                }
+                out.push_str(&token.to_string());
+                after_word = true;
+                space = false;
+            }
+            TokenTree::Group(group) => {
+                if space {
+                    out.push(' ');
+                }
+                write_group(group.delimiter(), group.stream(), out);
+                after_word = false;
+                space = false;
+            }
+            TokenTree::Punct(punct) => {
+                let c = punct.as_char();
+                let joint_arrow = c == '='
+                    && punct.spacing() == Spacing::Joint
+                    && matches!(tokens.peek(), Some(TokenTree::Punct(next)) if next.as_char() == '>');
+                if c == '=' && !out.ends_with(['<', '>', '!', '.'])
+                    || c == '$' && after_word
+                    || space && !matches!(c, '*' | '+' | '?')
+                {
+                    out.push(' ');
+                }
+                out.push(c);
+                if joint_arrow {
+                    tokens.next();
+                    out.push('>');
+                }
+                after_word = false;
+                space = matches!(c, ',' | ';' | '=');
+            }
+        }
+    }
+}
+
+fn write_group(delimiter: Delimiter, tokens: TokenStream, out: &mut String) {
+    let (open, close) = match delimiter {
+        Delimiter::Parenthesis => ("(", ")"),
+        Delimiter::Brace => ("{", "}"),
+        Delimiter::Bracket => ("[", "]"),
+        Delimiter::None => ("", ""),
+    };
+    out.push_str(open);
+    write_tokens(tokens, out);
+    out.push_str(close);
+}
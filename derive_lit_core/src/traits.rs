@@ -0,0 +1,109 @@
+//! The traits that literals construct and add through with `#[lit(traits = "...")]`, for generic code shared by every
+//! type with a derived literal
+
+/// Constructing an empty data structure for a literal
+pub trait LitNew {
+    fn lit_new() -> Self;
+}
+
+/// Adding an element to a vec-like or set-like data structure
+pub trait LitPush<T> {
+    fn lit_push(&mut self, elem: T);
+}
+
+/// Inserting an entry into a map-like data structure
+pub trait LitInsert<K, V> {
+    fn lit_insert(&mut self, key: K, val: V);
+}
+
+/// The impls for the collections of `std`, with the `std_impls` feature
+#[cfg(feature = "std_impls")]
+mod std_impls {
+    use super::{LitInsert, LitNew, LitPush};
+    use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+    use std::hash::{BuildHasher, Hash};
+
+    impl<T> LitNew for Vec<T> {
+        fn lit_new() -> Self {
+            Vec::new()
+        }
+    }
+
+    impl<T> LitPush<T> for Vec<T> {
+        fn lit_push(&mut self, elem: T) {
+            self.push(elem);
+        }
+    }
+
+    impl<T> LitNew for VecDeque<T> {
+        fn lit_new() -> Self {
+            VecDeque::new()
+        }
+    }
+
+    impl<T> LitPush<T> for VecDeque<T> {
+        fn lit_push(&mut self, elem: T) {
+            self.push_back(elem);
+        }
+    }
+
+    impl<T: Ord> LitNew for BinaryHeap<T> {
+        fn lit_new() -> Self {
+            BinaryHeap::new()
+        }
+    }
+
+    impl<T: Ord> LitPush<T> for BinaryHeap<T> {
+        fn lit_push(&mut self, elem: T) {
+            self.push(elem);
+        }
+    }
+
+    impl<T, S: Default> LitNew for HashSet<T, S> {
+        fn lit_new() -> Self {
+            HashSet::default()
+        }
+    }
+
+    impl<T: Eq + Hash, S: BuildHasher> LitPush<T> for HashSet<T, S> {
+        fn lit_push(&mut self, elem: T) {
+            self.insert(elem);
+        }
+    }
+
+    impl<T> LitNew for BTreeSet<T> {
+        fn lit_new() -> Self {
+            BTreeSet::new()
+        }
+    }
+
+    impl<T: Ord> LitPush<T> for BTreeSet<T> {
+        fn lit_push(&mut self, elem: T) {
+            self.insert(elem);
+        }
+    }
+
+    impl<K, V, S: Default> LitNew for HashMap<K, V, S> {
+        fn lit_new() -> Self {
+            HashMap::default()
+        }
+    }
+
+    impl<K: Eq + Hash, V, S: BuildHasher> LitInsert<K, V> for HashMap<K, V, S> {
+        fn lit_insert(&mut self, key: K, val: V) {
+            self.insert(key, val);
+        }
+    }
+
+    impl<K, V> LitNew for BTreeMap<K, V> {
+        fn lit_new() -> Self {
+            BTreeMap::new()
+        }
+    }
+
+    impl<K: Ord, V> LitInsert<K, V> for BTreeMap<K, V> {
+        fn lit_insert(&mut self, key: K, val: V) {
+            self.insert(key, val);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, Block, Expr, Ident, LitStr, Result, Token};
+
+/// The input to `__dir_tree!`, e.g. `{ MyFs::new() } "src" => { "main.rs" => "fn main() {}" }, "README.md" => ""`
+pub struct DirTree {
+    new: Block,
+    nodes: Vec<Node>,
+}
+
+/// An entry of a directory tree literal
+enum Node {
+    /// `"name" => { ... }`, a directory and the entries inside it
+    Dir(LitStr, Vec<Node>),
+    /// `"name" => contents`, a file
+    File(LitStr, Box<Expr>),
+}
+
+impl Parse for DirTree {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(DirTree {
+            new: input.parse()?,
+            nodes: parse_nodes(input)?,
+        })
+    }
+}
+
+/// Parses a comma-separated list of entries, where braces after the `=>` always make a directory
+fn parse_nodes(input: ParseStream) -> Result<Vec<Node>> {
+    let mut nodes = vec![];
+    while !input.is_empty() {
+        let name: LitStr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        if input.peek(syn::token::Brace) {
+            let dir;
+            braced!(dir in input);
+            nodes.push(Node::Dir(name, parse_nodes(&dir)?));
+        } else {
+            nodes.push(Node::File(name, Box::new(input.parse()?)));
+        }
+
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+    }
+    Ok(nodes)
+}
+
+impl DirTree {
+    pub fn expand(&self) -> TokenStream {
+        let new = &self.new;
+        let temp = Ident::new("temp", Span::mixed_site());
+        let mut stmts = TokenStream::new();
+        add_nodes(&temp, None, &self.nodes, &mut stmts);
+
+        quote! {
+            {
+                let mut #temp = #new;
+                #stmts
+                #temp
+            }
+        }
+    }
+}
+
+/// Adds the statements adding `nodes`, whose paths are joined onto the path of the directory they're in, if any
+///
+/// Directories are added before the entries inside them.
+fn add_nodes(temp: &Ident, dir: Option<&str>, nodes: &[Node], stmts: &mut TokenStream) {
+    for node in nodes {
+        let name = match node {
+            Node::Dir(name, _) | Node::File(name, _) => name,
+        };
+        let path = match dir {
+            Some(dir) => format!("{}/{}", dir, name.value()),
+            None => name.value(),
+        };
+        let lit = LitStr::new(&path, name.span());
+        match node {
+            Node::Dir(_, nodes) => {
+                stmts.extend(quote!(#temp.add_dir(#lit);));
+                add_nodes(temp, Some(&path), nodes, stmts);
+            }
+            Node::File(_, contents) => stmts.extend(quote!(#temp.add_file(#lit, #contents);)),
+        }
+    }
+}
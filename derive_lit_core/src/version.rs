@@ -0,0 +1,126 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Lit, Path, Result, Token};
+
+/// The input to `__version!`, e.g. `semver::Version::new; "1.2.3"`, with the constructor and its `;` being optional
+pub struct VersionKey {
+    /// The constructor given the major, minor and patch numbers, if the key is converted
+    ctor: Option<Path>,
+    key: Lit,
+}
+
+impl Parse for VersionKey {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ctor = if input.peek(Lit) {
+            None
+        } else {
+            let ctor = input.parse()?;
+            input.parse::<Token![;]>()?;
+            Some(ctor)
+        };
+        Ok(VersionKey {
+            ctor,
+            key: input.parse()?,
+        })
+    }
+}
+
+impl VersionKey {
+    /// Checks that the key is a semver version, and expands to it as it is or converted with the constructor
+    pub fn expand(&self) -> TokenStream {
+        let key = match &self.key {
+            Lit::Str(key) => key,
+            lit => {
+                return syn::Error::new_spanned(lit, "expected a version string, like \"1.2.3\"")
+                    .to_compile_error()
+            }
+        };
+        let (numbers, labeled) = match parse_version(&key.value()) {
+            Ok(version) => version,
+            Err(reason) => {
+                return syn::Error::new(key.span(), format!("invalid version: {}", reason))
+                    .to_compile_error()
+            }
+        };
+
+        match &self.ctor {
+            None => quote!(#key),
+            Some(_) if labeled => syn::Error::new(
+                key.span(),
+                "a version with a pre-release or build can't be converted with `#[lit(version)]`",
+            )
+            .to_compile_error(),
+            Some(ctor) => {
+                let numbers = numbers.iter().map(|&n| {
+                    let mut number = Literal::u64_unsuffixed(n);
+                    number.set_span(key.span());
+                    number
+                });
+                quote!(#ctor(#(#numbers),*))
+            }
+        }
+    }
+}
+
+/// Parses a semver version as its major, minor and patch numbers, and whether it has a pre-release or build
+fn parse_version(version: &str) -> std::result::Result<([u64; 3], bool), String> {
+    let (version, build) = match version.split_once('+') {
+        Some((version, build)) => (version, Some(build)),
+        None => (version, None),
+    };
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+
+    let parts: Vec<_> = core.split('.').collect();
+    if parts.len() != 3 {
+        return Err(format!("expected `major.minor.patch`, found `{}`", core));
+    }
+    let mut numbers = [0; 3];
+    for (number, part) in numbers.iter_mut().zip(&parts) {
+        *number = parse_number(part)?;
+    }
+
+    if let Some(pre) = pre {
+        for ident in pre.split('.') {
+            check_ident(ident, "pre-release")?;
+            if ident.bytes().all(|b| b.is_ascii_digit()) {
+                parse_number(ident)?;
+            }
+        }
+    }
+    if let Some(build) = build {
+        for ident in build.split('.') {
+            check_ident(ident, "build")?;
+        }
+    }
+
+    Ok((numbers, pre.is_some() || build.is_some()))
+}
+
+/// Parses a numeric part of a version, which has no leading zeros
+fn parse_number(part: &str) -> std::result::Result<u64, String> {
+    if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("`{}` isn't a number", part));
+    }
+    if part.len() > 1 && part.starts_with('0') {
+        return Err(format!("`{}` has a leading zero", part));
+    }
+    part.parse().map_err(|_| format!("`{}` is too large", part))
+}
+
+/// Checks an identifier of the pre-release or build, which is non-empty and alphanumeric or `-`
+fn check_ident(ident: &str, what: &str) -> std::result::Result<(), String> {
+    if ident.is_empty() {
+        return Err(format!("the {} has an empty identifier", what));
+    }
+    match ident
+        .chars()
+        .find(|&c| !c.is_ascii_alphanumeric() && c != '-')
+    {
+        Some(c) => Err(format!("the {} has an unexpected `{}`", what, c)),
+        None => Ok(()),
+    }
+}
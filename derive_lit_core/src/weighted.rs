@@ -0,0 +1,137 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Block, Expr, ExprLit, Ident, Lit, Result, Token};
+
+use crate::check;
+
+/// The input to `__weighted!`, e.g. `{ MyStruct::new() } add, 100; "common": 80, "rare": 20`
+pub struct Weighted {
+    new: Block,
+    method: Ident,
+    /// The total the weights must sum to, when they're all numeric literals
+    sum: Option<Lit>,
+    entries: Vec<(Expr, Expr)>,
+}
+
+impl Parse for Weighted {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let new = input.parse()?;
+        let method = input.parse()?;
+        let sum = if input.parse::<Option<Token![,]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        input.parse::<Token![;]>()?;
+
+        let mut entries = vec![];
+        while !input.is_empty() {
+            let elem = parse_until_colon(input)?;
+            input.parse::<Token![:]>()?;
+            let weight = input.parse()?;
+            entries.push((elem, weight));
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(Weighted {
+            new,
+            method,
+            sum,
+            entries,
+        })
+    }
+}
+
+/// Parses an expression up to the `:` before its weight, since `expr: weight` would otherwise parse as type ascription
+///
+/// A `:` that the tokens before it don't make a whole expression in front of, like the one in `|x: u8| x`, is part of
+/// the expression. If no `:` ends a whole expression, the error is that of the tokens before the first one.
+fn parse_until_colon(input: ParseStream) -> Result<Expr> {
+    let mut tokens = TokenStream::new();
+    let mut first_err = None;
+    loop {
+        while !input.is_empty() && (!input.peek(Token![:]) || input.peek(Token![::])) {
+            if input.peek(Token![::]) {
+                let colons = input.parse::<Token![::]>()?;
+                tokens.extend(quote!(#colons));
+            } else {
+                tokens.extend(Some(input.parse::<TokenTree>()?));
+            }
+        }
+
+        if tokens.is_empty() {
+            return Err(input.error("expected an element"));
+        }
+        match syn::parse2(tokens.clone()) {
+            Ok(elem) => return Ok(elem),
+            Err(err) => {
+                let err = first_err.get_or_insert(err);
+                if input.is_empty() {
+                    return Err(err.clone());
+                }
+                let colon = input.parse::<Token![:]>()?;
+                tokens.extend(quote!(#colon));
+            }
+        }
+    }
+}
+
+impl Weighted {
+    /// Checks that the weights sum to the configured total, if they're all numeric literals
+    fn check_sum(&self) -> Result<()> {
+        let sum = match &self.sum {
+            Some(sum) => sum,
+            None => return Ok(()),
+        };
+        let expected = check::value(&Expr::Lit(ExprLit {
+            attrs: vec![],
+            lit: sum.clone(),
+        }))?;
+
+        let weights = self.entries.iter().map(|(_, weight)| weight);
+        let actual = match weights
+            .clone()
+            .map(|weight| check::value(weight).ok())
+            .sum::<Option<f64>>()
+        {
+            Some(actual) => actual,
+            None => return Ok(()),
+        };
+
+        if (actual - expected).abs() > f64::EPSILON * expected.abs().max(1.0) * 16.0 {
+            let weights = weights.map(check::ungroup);
+            return Err(syn::Error::new_spanned(
+                quote!(#(#weights)*),
+                format!("weights sum to {}, expected {}", actual, expected),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn expand(&self) -> TokenStream {
+        if let Err(err) = self.check_sum() {
+            return err.to_compile_error();
+        }
+
+        let new = &self.new;
+        let method = &self.method;
+        let temp = Ident::new("temp", Span::mixed_site());
+        let (elems, weights): (Vec<_>, Vec<_>) = self.entries.iter().cloned().unzip();
+
+        quote! {
+            {
+                let mut #temp = #new;
+                #(
+                    #temp.#method(#elems, #weights);
+                )*
+
+                #temp
+            }
+        }
+    }
+}
@@ -4,237 +4,2202 @@
 //!
 //! #[derive(VecLit)]
 //! struct GroceryList {
-//! 	num_items: usize,
-//! 	item_ids: Vec<usize>
+//!     num_items: usize,
+//!     item_ids: Vec<usize>
 //! }
 //!
 //! impl GroceryList {
-//! 	fn new() -> Self {
-//! 		Self {
-//! 			num_items: 0,
-//! 			item_ids: vec![]
-//! 		}
-//! 	}
+//!     fn new() -> Self {
+//!         Self {
+//!             num_items: 0,
+//!             item_ids: vec![]
+//!         }
+//!     }
 //!
-//! 	fn push(&mut self, item_id: usize) {
-//! 		self.item_ids.push(item_id);
-//! 	}
+//!     fn push(&mut self, item_id: usize) {
+//!         self.item_ids.push(item_id);
+//!     }
 //! }
 //!
 //! fn main() {
-//! 	let groceries = grocery_list![
-//! 		0,
-//! 		9,
-//! 		8,
-//! 		5
-//! 	];
+//!     let groceries = grocery_list![
+//!         0,
+//!         9,
+//!         8,
+//!         5
+//!     ];
 //!
-//! 	// do something intersting with your GroceryList...
+//!     // do something intersting with your GroceryList...
 //! }
 //! ```
+//!
+//! There are derives for vec-like, set-like and map-like data structures, and for a few more specific ones like sparse matrices, histograms and trees. Each derive documents the methods it needs and the literals its macro accepts. The literal syntax and the `#[lit(...)]` options they share are documented on [`VecLit`], and the ones for map-like data structures on [`MapLit`].
+//!
+//! The macros are generated by [`derive_lit_core`](https://docs.rs/derive_lit_core), which build scripts and other procedural macros can also call to generate literal macros for types of their own.
+//!
+//! With the `std_lits` feature, there are also ready-made literal macros for the collections of `std`, which are `hash_map!`, `btree_map!`, `hash_set!`, `btree_set!` and `deque!`.
+//!
+//! With the `nightly_notes` feature, which needs a nightly compiler, each derive also emits a note at the deriving type naming the macros it generated and the patterns of their arms, to see what a derive gave without expanding it.
+
+#![cfg_attr(feature = "nightly_notes", feature(proc_macro_diagnostic))]
 
 extern crate proc_macro;
 
-use heck::*;
-use quote::{quote};
-use syn::{
-    parse_macro_input, Data, DeriveInput, Ident
-};
+use derive_lit_core::Kind;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Converts the output tokens for the compiler, first noting the macros they define with the `nightly_notes` feature
+fn with_notes(tokens: proc_macro2::TokenStream) -> proc_macro::TokenStream {
+    #[cfg(feature = "nightly_notes")]
+    for (name, arms) in derive_lit_core::macro_arms(&tokens) {
+        let arms: Vec<_> = arms
+            .iter()
+            .map(|arm| format!("\n    {}!{}", name, arm))
+            .collect();
+        proc_macro::Diagnostic::spanned(
+            name.span().unwrap(),
+            proc_macro::Level::Note,
+            format!("generated `{}!`, with the arms{}", name, arms.concat()),
+        )
+        .emit();
+    }
+    proc_macro::TokenStream::from(tokens)
+}
+
+/// A derive for auto-generating a macro to create literal values for vec-like data structures
+///
+/// The vec-like data structure must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn push(elem)`
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::VecLit;
+/// # #[derive(VecLit)]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn push(&mut self, elem: usize) {}}
+/// let x: MyStruct = my_struct! [0, 9, 3, 4, 5];
+/// ```
+///
+/// `push` can be generic, so a literal can mix elements of any types it accepts, and an empty literal needs no element type.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// struct Names(Vec<String>);
+/// # impl Names { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: impl Into<String>) {self.0.push(elem.into())}}
+///
+/// let x = names!["a", String::from("b"), 'c'];
+/// assert_eq!(x.0, ["a", "b", "c"]);
+/// assert!(names![].0.is_empty());
+/// ```
+///
+/// # Literal syntax
+///
+/// Besides a plain list of elements, the literals of `VecLit`, and of the other derives for vec-like and set-like data structures, accept the following forms. The forms of map-like literals are documented on [`MapLit`].
+///
+/// ## Capacity prefix
+///
+/// For vec-like and set-like data structures, `my_struct![cap = n; ...]` constructs with `with_capacity(n)` instead of `new()`, for literals that will be extended later. The capacity can be any expression.
+/// ```
+/// # use derive_lit::VecLit;
+/// # #[derive(VecLit)]
+/// # struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn with_capacity(n: usize) -> Self {Self(Vec::with_capacity(n))} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+/// let groceries = grocery_list![cap = 1024; 0, 9, 8];
+/// assert!(groceries.0.capacity() >= 1024);
+/// ```
+///
+/// ## Conditional elements
+///
+/// In vec-like and set-like literals, elements can be preceded by `#[cfg(...)]` to drop them from the literal when the predicate doesn't hold.
+/// ```
+/// # use derive_lit::VecLit;
+/// # #[derive(VecLit)]
+/// # struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+/// let groceries = grocery_list![0, #[cfg(feature = "extra")] 9, 8];
+/// assert_eq!(groceries.0, vec![0, 8]);
+/// ```
+///
+/// ## Iterated elements
+///
+/// In vec-like and set-like literals, an element written `in iter` adds every item of `iter`, so ranges and other iterators can be mixed with plain elements.
+/// ```
+/// # use derive_lit::SetLit;
+/// # use std::collections::BTreeSet;
+/// # #[derive(SetLit)]
+/// # struct MySet(BTreeSet<usize>);
+/// # impl MySet { fn new() -> Self {Self(BTreeSet::new())} fn insert(&mut self, elem: usize) {self.0.insert(elem);}}
+/// let x = my_set! { in 0..3, 42, in 100..=101 };
+/// assert_eq!(x.0.into_iter().collect::<Vec<_>>(), vec![0, 1, 2, 42, 100, 101]);
+/// ```
+///
+/// ## Included elements
+///
+/// In vec-like and set-like literals, an element written `include "path"` reads the file at `path` at compile time and adds each of its non-blank lines as a string literal, so large word lists and lookup tables don't need to be inlined by hand. Like paths in `Cargo.toml`, `path` is relative to the directory of the crate's manifest, and changing the file rebuilds the literal.
+/// ```
+/// # use derive_lit::VecLit;
+/// # #[derive(VecLit)]
+/// # struct Lines(Vec<&'static str>);
+/// # impl Lines { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: &'static str) {self.0.push(elem)}}
+/// let x = lines!(include "Cargo.toml");
+/// assert_eq!(x.0[0], "[package]");
+/// ```
+///
+/// ## Bindings
+///
+/// An element or entry written `let pat = val;` binds `pat` for the elements and entries after it, so a value used by several of them doesn't need to be hoisted out of the literal. The binding ends with its `;`, and isn't followed by a `,`.
+/// ```
+/// # use derive_lit::VecLit;
+/// # #[derive(VecLit)]
+/// # struct Offsets(Vec<usize>);
+/// # impl Offsets { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+/// # fn base() -> usize { 10 }
+/// let x = offsets![let base = base(); base, base + 1, base + 2];
+/// assert_eq!(x.0, vec![10, 11, 12]);
+/// ```
+///
+/// ## Repeated elements
+///
+/// For vec-like and set-like data structures, `my_struct![elem; n]` adds `n` clones of `elem`, like `vec!` does. The count can be any expression evaluated at runtime, and the element must implement `Clone`.
+/// ```
+/// # use derive_lit::VecLit;
+/// # #[derive(VecLit)]
+/// # struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+/// let n = 3;
+/// let groceries = grocery_list![7; n];
+/// assert_eq!(groceries.0, vec![7, 7, 7]);
+/// ```
+///
+/// ## From an iterator
+///
+/// `my_struct!(from iter)` adds every item of `iter`, like `collect` does for data structures that implement `FromIterator`. For map-like data structures the items are `(key, val)` pairs.
+/// ```
+/// # use derive_lit::VecLit;
+/// # #[derive(VecLit)]
+/// # struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+/// let groceries = grocery_list!(from (0..3).map(|i| i * 2));
+/// assert_eq!(groceries.0, vec![0, 2, 4]);
+/// ```
+///
+/// ## Seed value
+///
+/// `my_struct![from base; ...]` adds the elements or entries to `base`, an existing value of the data structure, instead of to a new one. This suits building defaults plus overrides. `base` is moved into the literal, unless the data structure has `#[lit(clone_seed)]`, which clones it instead.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit, Clone)]
+/// #[lit(clone_seed)]
+/// struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// let staples = grocery_list![0, 9];
+/// let groceries = grocery_list![from staples; 8, 4];
+/// assert_eq!((staples.0, groceries.0), (vec![0, 9], vec![0, 9, 8, 4]));
+/// ```
+///
+/// ## Generic arguments
+///
+/// For a generic data structure, a literal can start with `::<...>` to give its type arguments, like a turbofish. This is the place to name a type parameter that the elements don't determine, such as one only used by a `PhantomData`, and `_` leaves an argument to be inferred.
+/// ```
+/// # use derive_lit::VecLit;
+/// use std::marker::PhantomData;
+///
+/// #[derive(VecLit)]
+/// struct TypedVec<T, Unit>(Vec<T>, PhantomData<Unit>);
+/// # impl<T, Unit> TypedVec<T, Unit> { fn new() -> Self {Self(vec![], PhantomData)} fn push(&mut self, elem: T) {self.0.push(elem)}}
+///
+/// struct Meters;
+///
+/// let x = typed_vec![::<_, Meters> 1.5, 2.0];
+/// assert_eq!(x.0.len(), 2);
+/// ```
+///
+/// # Options
+///
+/// Each derive can be configured with a `#[lit(...)]` attribute on the data structure. The options below are common to the derives, and those only for map-like data structures are documented on [`MapLit`].
+///
+/// ## Naming and exporting
+///
+/// The generated macro is also imported into the module of the data structure, so it can be invoked anywhere in that module, even above the `#[derive]`, and from elsewhere in the crate by its path. This is skipped for a data structure that is already named like its macro, which would clash with the import.
+/// ```
+/// # use derive_lit::VecLit;
+/// fn groceries() -> GroceryList {
+///     grocery_list![0, 9]
+/// }
+///
+/// #[derive(VecLit)]
+/// struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+/// # assert_eq!(groceries().0, vec![0, 9]);
+/// ```
+///
+/// When the snake case of the name is a keyword, the macro is named with a raw identifier instead.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// struct Loop(Vec<usize>);
+/// # impl Loop { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// let x = r#loop![0, 9];
+/// # assert_eq!(x.0, vec![0, 9]);
+/// ```
+///
+/// ### `rename` and `acronyms`
+///
+/// By default the macro is named with the snake case of the data structure's name, with a run of capitals treated as one word, so `HTTPServerPool` gets `http_server_pool!`. `#[lit(acronyms = "merge")]` instead keeps such a run together with the word after it, giving `httpserver_pool!`, and `#[lit(rename = "name")]` names the macro `name!` outright.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(acronyms = "merge")]
+/// struct HTTPServerPool(Vec<usize>);
+/// # impl HTTPServerPool { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// #[derive(VecLit)]
+/// #[lit(rename = "pool")]
+/// struct ConnectionPool(Vec<usize>);
+/// # impl ConnectionPool { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// let x = httpserver_pool![80, 443];
+/// let y = pool![5432];
+/// # assert_eq!((x.0, y.0), (vec![80, 443], vec![5432]));
+/// ```
+///
+/// ### `cfg`
+///
+/// `#[lit(cfg = "...")]` wraps everything the derive generates in the given `#[cfg]`. This lets you keep the literal macro behind a feature flag.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(cfg = "feature = \"macros\"")]
+/// struct GroceryList {
+///     item_ids: Vec<usize>
+/// }
+/// ```
+///
+/// ### `export`
+///
+/// `#[lit(export)]` exports the macros from the crate root with `#[macro_export]`, so that other crates can invoke them. Since the literals then expand in those crates, they name the data structure by its path from `$crate`, which is the crate root for `#[lit(export)]` and the module at the path from the crate root for `#[lit(export = "path::to")]`. The data structure must be public there.
+/// ```
+/// mod shapes {
+///     # use derive_lit::VecLit;
+///     #[derive(VecLit)]
+///     #[lit(export = "shapes")]
+///     pub struct Polygon(pub Vec<(i32, i32)>);
+///     # impl Polygon { pub fn new() -> Self {Self(vec![])} pub fn push(&mut self, elem: (i32, i32)) {self.0.push(elem)}}
+/// }
+///
+/// fn main() {
+///     let x = shapes::polygon![(0, 0), (4, 0), (0, 3)];
+///     assert_eq!(x.0.len(), 3);
+/// }
+/// ```
+///
+/// The crates invoking the macros also need to depend on `derive_lit`, whose hidden macros the literals expand to. The macros invoke each other by their paths from `$crate`, so they can be invoked by path without importing them.
+/// ```
+/// mod shapes {
+///     # use derive_lit::VecLit;
+///     #[derive(VecLit)]
+///     #[lit(export = "shapes", assert_eq = "iter")]
+///     pub struct Polygon(pub Vec<(i32, i32)>);
+///     # impl Polygon { pub fn new() -> Self {Self(vec![])} pub fn push(&mut self, elem: (i32, i32)) {self.0.push(elem)} pub fn iter(&self) -> impl Iterator<Item = &(i32, i32)> {self.0.iter()}}
+/// }
+///
+/// fn main() {
+///     let x = shapes::polygon![(0, 0), (4, 0)];
+///     shapes::assert_polygon_eq!(x, [(0, 0), (4, 0)]);
+/// }
+/// ```
+///
+/// ### `reexport`
+///
+/// With `#[lit(export, reexport)]`, the macros are instead exported from the crate root under hidden names, and re-exported from the module of the data structure under their own names, like its other items. Other crates then invoke them by their path in that module, or import them from there with `use`, and the crate root isn't crowded with the macros of every module.
+/// ```
+/// mod shapes {
+///     # use derive_lit::VecLit;
+///     #[derive(VecLit)]
+///     #[lit(export = "shapes", reexport)]
+///     pub struct Polygon(pub Vec<(i32, i32)>);
+///     # impl Polygon { pub fn new() -> Self {Self(vec![])} pub fn push(&mut self, elem: (i32, i32)) {self.0.push(elem)}}
+/// }
+///
+/// fn main() {
+///     let x = crate::shapes::polygon![(0, 0), (4, 0), (0, 3)];
+///     assert_eq!(x.0.len(), 3);
+/// }
+/// ```
+///
+/// ## Constructing
+///
+/// ### `ctor`
+///
+/// `#[lit(ctor(method))]` constructs with `method()` instead of `new()`. `#[lit(ctor(method = "keyword"))]` adds a constructor selected by starting the literal with the keyword, followed by the arguments to `method` and a `;`. The attribute can be given several times.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(ctor(empty), ctor(with_capacity = "sized"))]
+/// struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn empty() -> Self {Self(vec![])} fn with_capacity(n: usize) -> Self {Self(Vec::with_capacity(n))} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// let x: GroceryList = grocery_list![0, 9];
+/// let y: GroceryList = grocery_list![sized 100; 0, 9];
+/// assert!(y.0.capacity() >= 100);
+/// ```
+///
+/// ### `default`
+///
+/// `#[lit(default)]` constructs with `Default::default()` instead of `new()`, for data structures that only implement `Default`.
+/// ```
+/// # use derive_lit::SetLit;
+/// # use std::collections::BTreeSet;
+/// #[derive(SetLit, Default)]
+/// #[lit(default)]
+/// struct Tags(BTreeSet<&'static str>);
+/// # impl Tags { fn insert(&mut self, elem: &'static str) {self.0.insert(elem);}}
+///
+/// let x = tags!["b", "a"];
+/// assert_eq!(x.0.into_iter().collect::<Vec<_>>(), vec!["a", "b"]);
+/// ```
+///
+/// ### `hasher`
+///
+/// `#[lit(hasher = "path")]` constructs with `with_hasher(path())` instead of `new()`, and with `with_capacity_and_hasher(n, path())` instead of `with_capacity(n)`, for hash-based data structures using a custom hasher. It can't be combined with `new_args` or another default constructor.
+/// ```
+/// # use derive_lit::SetLit;
+/// # use std::collections::{hash_map::DefaultHasher, HashSet};
+/// # use std::hash::BuildHasherDefault;
+/// type Hasher = BuildHasherDefault<DefaultHasher>;
+///
+/// #[derive(SetLit)]
+/// #[lit(hasher = "Hasher::default")]
+/// struct Ports(HashSet<u16, Hasher>);
+/// # impl Ports { fn with_hasher(h: Hasher) -> Self {Self(HashSet::with_hasher(h))} fn with_capacity_and_hasher(n: usize, h: Hasher) -> Self {Self(HashSet::with_capacity_and_hasher(n, h))} fn insert(&mut self, elem: u16) {self.0.insert(elem);}}
+///
+/// let x = ports![80];
+/// let y = ports![cap = 2; 80, 443];
+/// assert!(x.0.contains(&80) && y.0.contains(&443));
+/// ```
+///
+/// ### `new_args`
+///
+/// `#[lit(new_args)]` is for data structures whose `new` takes arguments, like a comparator or a config. The generated macro then starts with the arguments, followed by a `;` and the elements.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(new_args)]
+/// struct Bounded {
+///     max: usize,
+///     items: Vec<usize>
+/// }
+/// # impl Bounded { fn new(max: usize) -> Self {Self{max, items: vec![]}} fn push(&mut self, elem: usize) {self.items.push(elem.min(self.max))}}
+///
+/// let x: Bounded = bounded![5; 0, 9, 3];
+/// assert_eq!(x.items, vec![0, 5, 3]);
+/// ```
+///
+/// This covers probabilistic data structures too, like a bloom filter constructed from its capacity and false positive rate and then filled with `insert`.
+/// ```
+/// # use derive_lit::SetLit;
+/// #[derive(SetLit)]
+/// #[lit(new_args)]
+/// struct Bloom {
+///     bits: Vec<bool>,
+///     hashes: usize,
+/// }
+/// # impl Bloom {
+/// #     fn new(capacity: usize, fp_rate: f64) -> Self {Self{bits: vec![false; capacity * 10], hashes: (-fp_rate.log2()).ceil() as usize}}
+/// #     fn index(&self, elem: &str, seed: usize) -> usize {use std::hash::{Hash, Hasher}; let mut h = std::collections::hash_map::DefaultHasher::new(); (elem, seed).hash(&mut h); h.finish() as usize % self.bits.len()}
+/// #     fn insert(&mut self, elem: &str) {for seed in 0..self.hashes {let i = self.index(elem, seed); self.bits[i] = true;}}
+/// #     fn contains(&self, elem: &str) -> bool {(0..self.hashes).all(|seed| self.bits[self.index(elem, seed)])}
+/// # }
+///
+/// let x = bloom! { 1000, 0.01; "a", "b", "c" };
+/// assert!(x.contains("b"));
+/// ```
+///
+/// ### `new_in`
+///
+/// `#[lit(new_in)]` is for data structures that allocate from an arena or an allocator given to their `new_in`. A literal may then start with `in`, the allocator and a `;`, and without that it still uses `new`.
+/// ```
+/// # use derive_lit::VecLit;
+/// # use std::cell::Cell;
+/// struct Arena {
+///     used: Cell<usize>,
+/// }
+///
+/// #[derive(VecLit)]
+/// #[lit(new_in)]
+/// struct ArenaVec<'a> {
+///     arena: Option<&'a Arena>,
+///     items: Vec<usize>,
+/// }
+/// # impl<'a> ArenaVec<'a> {
+/// #     fn new() -> Self {Self{arena: None, items: vec![]}}
+/// #     fn new_in(arena: &'a Arena) -> Self {Self{arena: Some(arena), items: vec![]}}
+/// #     fn push(&mut self, elem: usize) {if let Some(arena) = self.arena {arena.used.set(arena.used.get() + 1)} self.items.push(elem)}
+/// # }
+///
+/// let arena = Arena { used: Cell::new(0) };
+/// let x: ArenaVec = arena_vec![in &arena; 1, 2, 3];
+/// let y: ArenaVec = arena_vec![4, 5];
+/// assert_eq!(arena.used.get(), 3);
+/// assert_eq!(y.items, vec![4, 5]);
+/// # let _ = x;
+/// ```
+///
+/// ### `async_new`
+///
+/// `#[lit(async_new)]` is for data structures constructed by an `async fn`, like handles to a remote store. The literal awaits the constructor, so the generated macro can only be used in `async` code.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(async_new)]
+/// struct Remote(Vec<usize>);
+/// # impl Remote { fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// impl Remote {
+///     async fn new() -> Self {
+///         // connect...
+///         Self(vec![])
+///     }
+/// }
+///
+/// async fn load() -> Remote {
+///     remote![0, 9, 8]
+/// }
+/// ```
+///
+/// ### `inline_capacity`
+///
+/// `#[lit(inline_capacity = n)]` is for data structures like `SmallVec` that store up to `n` elements inline. The generated macro constructs with `new()` when the literal fits in the inline capacity and with `with_capacity(len)` otherwise, so small literals don't spill and large ones don't reallocate. Elements that `#[cfg]` removes don't count. The data structure must also have `fn with_capacity(usize) -> Self`, and `#[lit(traits)]` doesn't apply, since `LitNew` doesn't take a capacity.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(inline_capacity = 4)]
+/// struct Small(Vec<usize>);
+/// # impl Small { fn new() -> Self {Self(vec![])} fn with_capacity(n: usize) -> Self {Self(Vec::with_capacity(n))} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// let x: Small = small! [0, 9];
+/// let y: Small = small! [0, 9, 3, 4, 5, #[cfg(any())] 7, 6];
+/// assert_eq!(y.0.capacity(), 6);
+/// ```
+///
+/// ### `setter`
+///
+/// `#[lit(setter(name = "method"))]` lets vec-like and set-like literals start with `name: val` pairs and a `;`, which pass each `val` to its setter before the elements are added. This way a single literal can both configure and fill a builder-like data structure. The attribute can be given several times, and the pairs can be in any order.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(setter(capacity = "set_capacity"), setter(growth = "set_growth"))]
+/// struct Buf {
+///     items: Vec<u8>,
+///     capacity: usize,
+///     growth: usize,
+/// }
+/// # impl Buf { fn new() -> Self {Self{items: vec![], capacity: 0, growth: 1}} fn push(&mut self, elem: u8) {self.items.push(elem)} fn set_capacity(&mut self, capacity: usize) {self.capacity = capacity} fn set_growth(&mut self, growth: usize) {self.growth = growth}}
+///
+/// let x = buf! { capacity: 64, growth: 2; 1, 2, 3 };
+/// assert_eq!((x.capacity, x.growth, x.items), (64, 2, vec![1, 2, 3]));
+/// ```
+///
+/// ## Adding elements
+///
+/// ### `skip_none`
+///
+/// `#[lit(skip_none)]` makes the elements of vec-like and set-like literals `Option`s, adding only the ones that are `Some`. This is handy for assembling a literal from optional values.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(skip_none)]
+/// struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// let extra: Option<usize> = None;
+/// let groceries = grocery_list![Some(0), extra, Some(8)];
+/// assert_eq!(groceries.0, vec![0, 8]);
+/// ```
+///
+/// ### `strict`
+///
+/// `#[lit(strict)]` makes set-like literals panic when `insert` returns `false`, naming the element that was already in the set. This catches typos in long literals that would otherwise shrink the set silently.
+/// ```should_panic
+/// # use derive_lit::SetLit;
+/// # use std::collections::HashSet;
+/// #[derive(SetLit)]
+/// #[lit(strict)]
+/// struct Keywords(HashSet<&'static str>);
+/// # impl Keywords { fn new() -> Self {Self(HashSet::new())} fn insert(&mut self, elem: &'static str) -> bool {self.0.insert(elem)}}
+///
+/// // panics with "duplicate element `"fn"` in literal"
+/// let x = keywords!["fn", "let", "fn"];
+/// ```
+///
+/// ### `sort`
+///
+/// `#[lit(sort)]` sorts the elements of vec-like and set-like literals, or the keys of map-like literals, at compile time, numerically for numbers and lexicographically for strings. Order-sensitive data structures then get sorted input however the literal is written. Sorting only happens when all of the elements or keys are literals of the same kind, and otherwise they're added in the order they're written.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(sort)]
+/// struct Thresholds(Vec<i32>);
+/// # impl Thresholds { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: i32) {self.0.push(elem)}}
+///
+/// let x = thresholds![100, -5, 20];
+/// assert_eq!(x.0, vec![-5, 20, 100]);
+/// ```
+///
+/// ### `bulk`
+///
+/// `#[lit(bulk = "from_sorted_iter")]` builds set-like and map-like literals whose elements or keys are all literals with the given constructor instead, which takes an array of the elements, or of `(key, val)` pairs, sorted at compile time. Ordered data structures can then be built in one pass rather than by inserting each entry. The elements or keys must be numbers, strings, chars or bytes of the same kind, and two equal ones are a compile error, so the array is strictly ascending. Literals with other elements or keys are built as usual.
+/// ```
+/// # use derive_lit::MapLit;
+/// use std::collections::BTreeMap;
+///
+/// #[derive(MapLit)]
+/// #[lit(bulk = "from_sorted_iter")]
+/// struct Index(BTreeMap<u32, &'static str>);
+/// # impl Index { fn new() -> Self {Self(BTreeMap::new())} fn insert(&mut self, key: u32, val: &'static str) {self.0.insert(key, val);}}
+///
+/// impl Index {
+///     fn from_sorted_iter(pairs: impl IntoIterator<Item = (u32, &'static str)>) -> Self {
+///         let pairs: Vec<_> = pairs.into_iter().collect();
+///         assert!(pairs.windows(2).all(|pair| pair[0].0 < pair[1].0));
+///         Self(pairs.into_iter().collect())
+///     }
+/// }
+///
+/// let x = index! { 30 => "c", 10 => "a", 20 => "b" };
+/// assert_eq!(x.0.keys().collect::<Vec<_>>(), [&10, &20, &30]);
+///
+/// let key = 40;
+/// let y = index! { key => "d" };
+/// assert_eq!(y.0[&40], "d");
+/// ```
+/// ```compile_fail
+/// # use derive_lit::MapLit;
+/// # use std::collections::BTreeMap;
+/// # #[derive(MapLit)]
+/// # #[lit(bulk = "from_sorted_iter")]
+/// # struct Index(BTreeMap<u32, &'static str>);
+/// # impl Index { fn new() -> Self {Self(BTreeMap::new())} fn insert(&mut self, key: u32, val: &'static str) {self.0.insert(key, val);} fn from_sorted_iter(pairs: impl IntoIterator<Item = (u32, &'static str)>) -> Self {Self(pairs.into_iter().collect())}}
+/// // duplicate key `10`
+/// let x = index! { 10 => "a", 10 => "b" };
+/// ```
+///
+/// ### `reverse`
+///
+/// `#[lit(reverse)]` adds the elements of vec-like and set-like literals in the reverse of the order they're written, so for a stack-like data structure the first element written ends up on top. The items of `in` elements and `from` iterators are reversed too, and elements are also evaluated in reverse, which is why `let` bindings can't be used.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(reverse)]
+/// struct Stack(Vec<usize>);
+/// # impl Stack { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+/// # impl Stack { fn pop(&mut self) -> Option<usize> {self.0.pop()}}
+///
+/// let mut x = stack![1, in 2..4];
+/// assert_eq!(x.pop(), Some(1));
+/// assert_eq!(x.0, vec![3, 2]);
+/// ```
+///
+/// ### `validate`
+///
+/// `#[lit(validate = "path::to::is_valid")]` checks every literal element of vec-like and set-like literals with the given `const fn`, which takes an element and returns whether it's valid. An invalid literal, like an empty name or an out-of-range id, is a compile error at the element, and other elements are added unchecked.
+/// ```
+/// # use derive_lit::VecLit;
+/// const fn is_valid(name: &str) -> bool {
+///     !name.is_empty()
+/// }
+///
+/// #[derive(VecLit)]
+/// #[lit(validate = "is_valid")]
+/// struct Names(Vec<String>);
+/// # impl Names { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: impl Into<String>) {self.0.push(elem.into())}}
+///
+/// let user = String::from("carol");
+/// let x = names!["alice", "bob", user];
+/// assert_eq!(x.0, vec!["alice", "bob", "carol"]);
+/// ```
+///
+/// ### `range`
+///
+/// `#[lit(range = "lo..=hi")]` makes integer literal elements of vec-like and set-like literals outside of the range a compile error at the number, as a guard for byte tables and tables of enum ordinals. Either bound can be left out, and other elements are added unchecked.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(range = "0..=255")]
+/// struct Palette(Vec<u32>);
+/// # impl Palette { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: u32) {self.0.push(elem)}}
+///
+/// let x = palette![0, 128, 255];
+/// assert_eq!(x.0, vec![0, 128, 255]);
+/// ```
+/// ```compile_fail
+/// # use derive_lit::VecLit;
+/// # #[derive(VecLit)]
+/// # #[lit(range = "0..=255")]
+/// # struct Palette(Vec<u32>);
+/// # impl Palette { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: u32) {self.0.push(elem)}}
+/// // `256` is outside of the range `0..=255`
+/// let x = palette![0, 256];
+/// ```
+///
+/// ### `non_zero`
+///
+/// `#[lit(non_zero = "NonZeroU32")]` is for collections of a `NonZero` type. Integer literal elements are converted to it in a `const`, so they can be written as plain numerals and a zero is a compile error at the element, and other elements are added as they are.
+/// ```
+/// # use derive_lit::VecLit;
+/// use std::num::NonZeroU32;
+///
+/// #[derive(VecLit)]
+/// #[lit(non_zero = "NonZeroU32")]
+/// struct Ids(Vec<NonZeroU32>);
+/// # impl Ids { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: NonZeroU32) {self.0.push(elem)}}
+///
+/// let next = NonZeroU32::new(3).unwrap();
+/// let x = ids![1, 2, next];
+/// assert_eq!(x.0.iter().map(|id| id.get()).collect::<Vec<_>>(), vec![1, 2, 3]);
+/// ```
+///
+/// ### `cow`
+///
+/// `#[lit(cow)]` is for collections of `Cow`s, like `Cow<'static, str>`. String literal elements are wrapped in `Cow::Borrowed`, so they don't allocate, and other elements are converted with `Into` and wrapped in `Cow::Owned`. The items of `in` elements and `from` iterators are added as they are.
+/// ```
+/// # use derive_lit::VecLit;
+/// use std::borrow::Cow;
+///
+/// #[derive(VecLit)]
+/// #[lit(cow)]
+/// struct Names(Vec<Cow<'static, str>>);
+/// # impl Names { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: Cow<'static, str>) {self.0.push(elem)}}
+///
+/// let x = names!["root", format!("user-{}", 7)];
+/// assert!(matches!(x.0[0], Cow::Borrowed("root")));
+/// assert!(matches!(x.0[1], Cow::Owned(_)));
+/// ```
+///
+/// ### `splat`
+///
+/// `#[lit(splat)]` makes a byte string element add each of its bytes, in order, instead of being added as one element. This keeps binary fixtures like magic numbers and headers readable, without a comma between every byte.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(splat)]
+/// struct Packet(Vec<u8>);
+/// # impl Packet { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: u8) {self.0.push(elem)}}
+///
+/// let x = packet![b"\x7fELF", 2, 1];
+/// assert_eq!(x.0, vec![0x7f, b'E', b'L', b'F', 2, 1]);
+/// ```
+///
+/// `#[lit(splat = "chars")]` and `#[lit(splat = "bytes")]` split string literal elements too, into their `char`s or bytes, so a literal of a `char` container can be written `my_chars!["hello", '!']` instead of doing the `.chars()` plumbing by hand.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(splat = "chars")]
+/// struct Word(Vec<char>);
+/// # impl Word { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: char) {self.0.push(elem)}}
+///
+/// let x = word!["hi", '!'];
+/// assert_eq!(x.0, vec!['h', 'i', '!']);
+/// ```
+///
+/// ### `copy`
+///
+/// `#[lit(copy = "Type")]` promises that elements are constant expressions of the `Copy` type `Type`. A plain list of elements is then stored in a `static` array that the literal copies from, so a huge numeric literal lives in read-only data instead of compiling to one `push` per element. Literals that are also `strict`, or that sort, reverse, check or convert their elements, still add them one by one, and `copy` can't be combined with `skip_none`, `cow` or `tokens`, whose elements aren't of the element type.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(copy = "u16")]
+/// struct Samples(Vec<u16>);
+/// # impl Samples { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: u16) {self.0.push(elem)}}
+///
+/// let x = samples![0, 100, 200, 0x1000];
+/// assert_eq!(x.0, vec![0, 100, 200, 4096]);
+///
+/// let y = samples![1, #[cfg(any())] 2, 3];
+/// assert_eq!(y.0, vec![1, 3]);
+/// ```
+///
+/// ### `trace`
+///
+/// `#[lit(trace)]` makes debug builds log every element or entry a literal adds with `eprintln!`, along with the file and line of the literal. This helps track down which literal filled a long-lived registry. The elements, or the keys and values, must implement `Debug`. `#[lit(trace = "path::to::macro")]` logs through another macro taking format arguments, like `log::debug`.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(trace)]
+/// struct Registry(Vec<&'static str>);
+/// # impl Registry { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: &'static str) {self.0.push(elem)}}
+///
+/// // logs `<file>:<line>: literal added "json"`, then the same for "yaml"
+/// let x = registry!["json", "yaml"];
+/// ```
+///
+/// ### `track_caller`
+///
+/// `#[lit(track_caller = "method")]` makes vec-like and set-like literals add each element with `method(elem, location)` instead of the derive's usual method, where `location` is the [`Location`](std::panic::Location) of the literal. Registries built from literals scattered across a codebase can then keep where each entry came from, in release builds too.
+/// ```
+/// # use derive_lit::VecLit;
+/// use std::panic::Location;
+///
+/// #[derive(VecLit)]
+/// #[lit(track_caller = "push_with_origin")]
+/// struct Registry(Vec<(&'static str, u32)>);
+/// # impl Registry { fn new() -> Self {Self(vec![])} }
+///
+/// impl Registry {
+///     fn push_with_origin(&mut self, elem: &'static str, loc: &'static Location<'static>) {
+///         self.0.push((elem, loc.line()));
+///     }
+/// }
+///
+/// let line = line!() + 1;
+/// let x = registry!["json", "yaml"];
+/// assert_eq!(x.0, vec![("json", line), ("yaml", line)]);
+/// ```
+///
+/// ### `tokens`
+///
+/// `#[lit(tokens = "path::to::translate")]` passes the tokens of each element to the `translate!` macro and adds what it expands to, instead of parsing elements as expressions. This lets you build small DSLs on top of a literal.
+/// ```
+/// # use derive_lit::VecLit;
+/// macro_rules! op {
+///     (push $n:literal) => { (0, $n) };
+///     (pop) => { (1, 0) };
+/// }
+///
+/// #[derive(VecLit)]
+/// #[lit(tokens = "op")]
+/// struct Program(Vec<(u8, u8)>);
+/// # impl Program { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: (u8, u8)) {self.0.push(elem)}}
+///
+/// let x = program![push 1, push 2, pop];
+/// assert_eq!(x.0, vec![(0, 1), (0, 2), (1, 0)]);
+/// ```
+///
+/// ### `csv`
+///
+/// `#[lit(csv = "method")]` lets a literal be written `my_struct!(csv "...")`, where the string is CSV that's parsed at compile time. Each record after the header is added with `method([...])`, an array of its fields as string literals, so tabular fixtures stay readable without being parsed at runtime. Fields may be quoted, with `""` for a quote, and a record with a different number of fields than the header fails to compile.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(csv = "push_row")]
+/// struct Users(Vec<(u32, &'static str)>);
+/// # impl Users { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: (u32, &'static str)) {self.0.push(elem)}}
+///
+/// impl Users {
+///     fn push_row(&mut self, [id, name]: [&'static str; 2]) {
+///         self.0.push((id.parse().unwrap(), name));
+///     }
+/// }
+///
+/// let x = users!(csv "id,name\n1,ada\n2,\"lovelace, a\"");
+/// assert_eq!(x.0, vec![(1, "ada"), (2, "lovelace, a")]);
+/// ```
+///
+/// ### `use`
+///
+/// `#[lit(use = "path::to::Enum::*")]` imports the path inside the expansion of every literal, so variants of an enum can be written without the enum, as in `Red => 1` instead of `Color::Red => 1`. This takes away a lot of noise from enum-keyed tables. The attribute can be given several times, and since the import is only inside the literal, it doesn't clash with anything around it.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// #[derive(PartialEq, Eq, Hash)]
+/// enum Color {
+///     Red,
+///     Green,
+/// }
+///
+/// #[derive(MapLit)]
+/// #[lit(use = "Color::*")]
+/// struct Palette(HashMap<Color, u32>);
+/// # impl Palette { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: Color, val: u32) {self.0.insert(key, val);}}
+///
+/// let x = palette! { Red => 0xff0000, Green => 0x00ff00 };
+/// assert_eq!(x.0[&Color::Green], 0x00ff00);
+/// ```
+///
+/// ## Generated items
+///
+/// ### `try_method`
+///
+/// `#[lit(try_method = "method")]` also generates a `try_` macro, like `try_my_struct!`, for a plain list of elements or entries that are added with the fallible `method`. The `try_` literal evaluates to `Ok` with the data structure, or to the first `Err` that `method` returns, without adding the elements after it, so call sites can handle the error with `?`.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(try_method = "try_push")]
+/// struct Pair(Vec<usize>);
+/// # impl Pair { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// impl Pair {
+///     fn try_push(&mut self, elem: usize) -> Result<(), usize> {
+///         if self.0.len() == 2 {
+///             return Err(elem);
+///         }
+///         self.0.push(elem);
+///         Ok(())
+///     }
+/// }
+///
+/// assert_eq!(try_pair![0, 9].map(|x| x.0), Ok(vec![0, 9]));
+/// assert_eq!(try_pair![0, 9, 8].map(|x| x.0), Err(8));
+/// ```
+///
+/// ### `try_from` and `capacity`
+///
+/// `#[lit(try_from = "T", capacity = n)]` implements `TryFrom<Vec<T>>` and `TryFrom<&[T]>` for vec-like and set-like data structures that hold at most `n` elements, for filling them from data only known at runtime. The elements are added like the items of a `from` literal, so options like `strict`, `skip_none`, `reverse`, `traits` and `track_caller` apply to them, while `cow` leaves them as they are. More than `n` of them are an error of a generated type named after the data structure, like `MyStructCapacityError`.
+/// ```
+/// # use derive_lit::VecLit;
+/// use std::convert::TryFrom;
+///
+/// #[derive(VecLit)]
+/// #[lit(try_from = "u8", capacity = 4)]
+/// struct Packet(Vec<u8>);
+/// # impl Packet { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: u8) {self.0.push(elem)}}
+///
+/// let x = Packet::try_from(vec![0, 9, 8]).unwrap();
+/// let y = Packet::try_from(&[1, 2, 3, 4, 5][..]);
+/// assert_eq!(x.0, vec![0, 9, 8]);
+/// assert_eq!(y.err(), Some(PacketCapacityError { len: 5, capacity: 4 }));
+/// ```
+///
+/// With `skip_none`, the elements are `Option`s, and only the `Some`s count towards the capacity.
+/// ```
+/// # use derive_lit::VecLit;
+/// # use std::convert::TryFrom;
+/// #[derive(VecLit)]
+/// #[lit(try_from = "u8", capacity = 2, skip_none)]
+/// struct Packet(Vec<u8>);
+/// # impl Packet { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: u8) {self.0.push(elem)}}
+///
+/// let x = Packet::try_from(vec![Some(0), None, Some(8)]).unwrap();
+/// assert_eq!(x.0, vec![0, 8]);
+/// ```
+///
+/// ### `from_str`
+///
+/// `#[lit(from_str = "T")]` implements `FromStr` for vec-like and set-like data structures, parsing a string written like the elements of a literal, such as `"0, 9, 8"`, with the `FromStr` of the element type `T`. For map-like data structures, `#[lit(from_str = "K => V")]` parses `key => val` entries with the key and value types. This lets config files and command line arguments use the same syntax as the literals in the code. An element or entry that fails to parse is an error of a generated type named after the data structure, like `MyStructParseError`.
+///
+/// The string is split at `,` and `=>` before the elements, keys and values are parsed, so they can't contain either.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// #[derive(MapLit)]
+/// #[lit(from_str = "String => u16")]
+/// struct Ports(HashMap<String, u16>);
+/// # impl Ports { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: String, val: u16) {self.0.insert(key, val);}}
+///
+/// let x: Ports = "http => 80, https => 443".parse().unwrap();
+/// assert_eq!(x.0["https"], 443);
+///
+/// let err = "http => 80, ssh".parse::<Ports>().err().unwrap();
+/// assert_eq!(err.entry, "ssh");
+/// ```
+///
+/// ### `fn`
+///
+/// `#[lit(fn = "T")]` generates a function in place of the macro, for APIs that would rather not export `macro_rules!`. Vec-like and set-like data structures get `fn from_elems<const N: usize>(elems: [T; N]) -> Self`, and map-like ones with `#[lit(fn = "K => V")]` get `fn from_entries<const N: usize>(entries: [(K, V); N]) -> Self`. The function constructs, adds and inserts the way the macro would, following options like `inline_capacity`, `hasher`, `skip_none`, `strict` and `on_duplicate`.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// #[derive(MapLit)]
+/// #[lit(fn = "&'static str => u16")]
+/// pub struct Ports(HashMap<&'static str, u16>);
+/// # impl Ports { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: &'static str, val: u16) {self.0.insert(key, val);}}
+///
+/// let x = Ports::from_entries([("http", 80), ("https", 443)]);
+/// assert_eq!(x.0["https"], 443);
+/// ```
+///
+/// ### `from_slice`
+///
+/// `#[lit(from_slice = "T")]` generates `fn from_slice(elems: &[T]) -> Self` for vec-like and set-like data structures alongside the macro, for callers with a slice at runtime rather than a literal. The elements are cloned and added the way the macro adds them, and the constructor follows `inline_capacity` with the length of the slice.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(from_slice = "usize")]
+/// struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// let ids = vec![0, 9, 8];
+/// let x = GroceryList::from_slice(&ids);
+/// assert_eq!(x.0, grocery_list![0, 9, 8].0);
+/// ```
+///
+/// ### `traits`
+///
+/// `#[lit(traits = "T")]` implements the `LitNew` and `LitPush<T>` traits of [`derive_lit_core`](https://docs.rs/derive_lit_core) for vec-like and set-like data structures, and `#[lit(traits = "K => V")]` implements `LitNew` and `LitInsert<K, V>` for map-like ones. The impls wrap the constructor and method the derive would call, and the macro, along with the functions of `fn`, `from_slice` and `try_from`, then constructs and adds through the traits, so generic code can build any of the data structures the same way. The crate needs to depend on `derive_lit_core` too.
+/// ```
+/// # use derive_lit::VecLit;
+/// use derive_lit_core::{LitNew, LitPush};
+///
+/// #[derive(VecLit)]
+/// #[lit(traits = "usize")]
+/// struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// fn first_ids<T: LitNew + LitPush<usize>>(n: usize) -> T {
+///     let mut ids = T::lit_new();
+///     for id in 0..n {
+///         ids.lit_push(id);
+///     }
+///     ids
+/// }
+///
+/// let x: GroceryList = first_ids(3);
+/// assert_eq!(x.0, grocery_list![0, 1, 2].0);
+/// ```
+///
+/// ### `to_lit_string` and `bound`
+///
+/// `#[lit(to_lit_string = "method")]` generates a `to_lit_string` method, the inverse of the literal, which renders the data structure as a literal of its macro from the `Debug` output of what `method` iterates over. This is the elements for vec-like and set-like data structures and the `(key, val)` pairs for map-like ones. The rendered literal can be pasted straight back into code, which makes it handy for debugging output and generated fixtures.
+///
+/// For a generic data structure, `#[lit(bound = "...")]` adds the `where` predicates that the elements need to be `Debug`.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::BTreeMap;
+/// #[derive(MapLit)]
+/// #[lit(to_lit_string = "iter", bound = "K: std::fmt::Debug, V: std::fmt::Debug")]
+/// struct Sorted<K: Ord, V>(BTreeMap<K, V>);
+/// # impl<K: Ord, V> Sorted<K, V> { fn new() -> Self {Self(BTreeMap::new())} fn insert(&mut self, key: K, val: V) {self.0.insert(key, val);}}
+///
+/// impl<K: Ord, V> Sorted<K, V> {
+///     fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+///         self.0.iter()
+///     }
+/// }
+///
+/// let x = sorted! { "b" => 2, "a" => 1 };
+/// assert_eq!(x.to_lit_string(), r#"sorted! { "a" => 1, "b" => 2 }"#);
+/// ```
+///
+/// The elements of a `VecFrontLit` are rendered in the order they'd be written, the reverse of how they're stored, so the rendering builds the same value again.
+/// ```
+/// # use derive_lit::VecFrontLit;
+/// # use std::collections::VecDeque;
+/// #[derive(VecFrontLit, PartialEq, Debug)]
+/// #[lit(to_lit_string = "iter")]
+/// struct Front(VecDeque<u8>);
+/// # impl Front { fn new() -> Self {Self(VecDeque::new())} fn push_front(&mut self, elem: u8) {self.0.push_front(elem)}}
+/// # impl Front { fn iter(&self) -> impl Iterator<Item = &u8> {self.0.iter()}}
+///
+/// let x = front![1, 2, 3];
+/// assert_eq!(x.to_lit_string(), "front![1, 2, 3]");
+/// assert_eq!(x, front![1, 2, 3]);
+/// ```
+///
+/// ### `push_macro`
+///
+/// `#[lit(push_macro)]` also generates a `_push` macro, like `my_struct_push!`, for adding to an existing value. It starts with a mutable place holding the value and a `;`, followed by anything the literal accepts after its head.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(push_macro)]
+/// struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// let mut groceries = grocery_list![0, 9];
+/// grocery_list_push!(groceries; 8, 4);
+/// assert_eq!(groceries.0, vec![0, 9, 8, 4]);
+/// ```
+///
+/// ### `map_macro`
+///
+/// `#[lit(map_macro)]` also generates a `_map` macro for vec-like and set-like data structures that can be filled by entries too, like `my_struct_map!`, which accepts everything the macros of `MapLit` do. Its entries are inserted with `insert`, or with the method given by `#[lit(map_macro = "method")]`, and both macros construct the data structure the same way, with the same constructors.
+/// ```
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(map_macro = "push_under", ctor(empty), ctor(with_capacity = "sized"))]
+/// struct Log(Vec<(u32, &'static str)>);
+/// # impl Log { fn empty() -> Self {Self(vec![])} fn with_capacity(n: usize) -> Self {Self(Vec::with_capacity(n))} fn push(&mut self, elem: &'static str) {self.0.push((self.0.len() as u32, elem))} fn push_under(&mut self, id: u32, elem: &'static str) {self.0.push((id, elem))}}
+///
+/// let x = log!["start", "stop"];
+/// let y = log_map! { sized 8; 7 => "start", 9 => "stop" };
+/// assert_eq!(x.0, vec![(0, "start"), (1, "stop")]);
+/// assert_eq!((y.0[1], y.0.capacity()), ((9, "stop"), 8));
+/// ```
+///
+/// ### `assert_eq`
+///
+/// `#[lit(assert_eq = "method")]` also generates an `assert_..._eq` macro for tests, like `assert_my_struct_eq!(actual, [...])`, which builds the expected value from the literal in `[...]` or `{...}` and compares it with `actual` through what `method` iterates over. Vec-like data structures are compared in order, and set-like and map-like ones regardless of order. When they differ, the panic points out the first element or key that does.
+/// ```should_panic
+/// # use derive_lit::VecLit;
+/// #[derive(VecLit)]
+/// #[lit(assert_eq = "iter")]
+/// struct GroceryList(Vec<usize>);
+/// # impl GroceryList { fn new() -> Self {Self(vec![])} fn push(&mut self, elem: usize) {self.0.push(elem)}}
+///
+/// impl GroceryList {
+///     fn iter(&self) -> impl Iterator<Item = &usize> {
+///         self.0.iter()
+///     }
+/// }
+///
+/// let groceries = grocery_list![0, 9, 8];
+/// assert_grocery_list_eq!(groceries, [0, 9, 8]);
+/// // panics with "assertion `groceries == grocery_list![0, 9, 4]` failed at element 2"
+/// assert_grocery_list_eq!(groceries, [0, 9, 4]);
+/// ```
+#[proc_macro_derive(VecLit, attributes(lit))]
+pub fn derive_vec_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::Vec))
+}
+
+/// A derive for auto-generating a macro to create literal values for vec-like data structures with a front at right end
+///
+/// The vec-like data structure must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn push_front(elem)`
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::VecFrontLit;
+/// # #[derive(VecFrontLit)]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn push_front(&mut self, elem: usize) {}}
+/// let x: MyStruct = my_struct! [0, 9, 3, 4, 5]; // front at right
+/// ```
+#[proc_macro_derive(VecFrontLit, attributes(lit))]
+pub fn derive_vec_front_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::VecFront))
+}
+
+/// A derive for auto-generating a macro to create literal values for set-like data structures
+///
+/// The set-like data structure must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn insert(elem)`
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::SetLit;
+/// # #[derive(SetLit)]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn insert(&mut self, elem: usize) {}}
+/// let x: MyStruct = my_struct! {0, 9, 3, 4, 5};
+/// ```
+///
+/// As with `VecLit`, `insert` can be generic over the elements it accepts.
+/// ```
+/// # use derive_lit::SetLit;
+/// # use std::collections::BTreeSet;
+/// #[derive(SetLit)]
+/// struct Tags(BTreeSet<String>);
+/// # impl Tags { fn new() -> Self {Self(BTreeSet::new())} fn insert(&mut self, elem: impl Into<String>) {self.0.insert(elem.into());}}
+///
+/// let x = tags! {"b", String::from("a")};
+/// assert_eq!(x.0.into_iter().collect::<Vec<_>>(), ["a", "b"]);
+/// assert!(tags! {}.0.is_empty());
+/// ```
+#[proc_macro_derive(SetLit, attributes(lit))]
+pub fn derive_set_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::Set))
+}
+
+/// A derive for auto-generating a macro to create literal values for map-like data structures
+///
+/// The map-like data structure must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn insert(key, val)`
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::MapLit;
+/// # #[derive(MapLit)]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn insert(&mut self, key: &'static str, val: usize) {}}
+/// let x: MyStruct = my_struct! {
+///     "a" => 0,
+///     "b" => 0,
+///     "c" => 7
+/// };
+/// ```
+///
+/// # Literal syntax
+///
+/// Besides a plain list of `key => val` entries, map-like literals accept the following forms, along with the forms for setting up the data structure that are documented on [`VecLit`].
+///
+/// ## Formatted keys
+///
+/// In map-like literals, a key written `fmt "..."` is expanded through `format!`, so computed string keys don't need to be wrapped by hand. Arguments are captured from the surrounding scope by name.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// # #[derive(MapLit)]
+/// # struct MyMap(HashMap<String, usize>);
+/// # impl MyMap { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: String, val: usize) {self.0.insert(key, val);}}
+/// let id = 7;
+/// let x = my_map! {
+///     fmt "user:{id}" => id,
+///     "admin".to_string() => 0
+/// };
+/// assert_eq!(x.0["user:7"], 7);
+/// ```
+///
+/// ## Spread entries
+///
+/// In map-like literals, an entry written `..map` inserts every `(key, val)` pair of `map`, in its place among the other entries. With the usual insertion, entries after it override its pairs and entries before it are overridden, which suits layering overrides on top of defaults.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// # #[derive(MapLit)]
+/// # struct Config(HashMap<&'static str, u32>);
+/// # impl Config { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: &'static str, val: u32) {self.0.insert(key, val);}}
+/// let defaults = vec![("port", 80), ("workers", 4)];
+/// let x = config! { "workers" => 1, ..defaults, "port" => 8080 };
+/// assert_eq!((x.0["port"], x.0["workers"]), (8080, 4));
+/// ```
+///
+/// ## Zipped entries
+///
+/// A map-like literal may also be written `my_struct! { keys: [...]; values: [...] }`, pairing the keys and values by position. This is handy for table-driven tests, where the keys and values are often listed apart. Lists of different lengths fail to compile.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// # #[derive(MapLit)]
+/// # struct Config(HashMap<&'static str, u32>);
+/// # impl Config { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: &'static str, val: u32) {self.0.insert(key, val);}}
+/// let x = config! {
+///     keys: ["port", "workers"];
+///     values: [8080, 4]
+/// };
+/// assert_eq!((x.0["port"], x.0["workers"]), (8080, 4));
+/// ```
+/// ```compile_fail
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// # #[derive(MapLit)]
+/// # struct Config(HashMap<&'static str, u32>);
+/// # impl Config { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: &'static str, val: u32) {self.0.insert(key, val);}}
+/// // the `keys` and `values` of a literal must have the same length
+/// let x = config! { keys: ["port", "workers"]; values: [8080] };
+/// ```
+///
+/// ## Included JSON
+///
+/// In map-like literals, an entry written `include_json "path"` reads the JSON object in the file at `path` at compile time and inserts each of its members, with the key as a string literal and the value as the Rust literal it's written like. Arrays become arrays, while nested objects and `null`s, which have no literal, fail to compile. This bridges config files and literal maps without parsing at runtime. As with `include`, `path` is relative to the directory of the crate's manifest. The values can be converted with `#[lit(json_value = "path::to::convert")]`, or with `value_into`.
+/// ```ignore
+/// // config.json: { "port": 8080, "workers": 4 }
+/// let x = config! { include_json "config.json", "debug" => 1 };
+/// assert_eq!((x.0["port"], x.0["debug"]), (8080, 1));
+/// ```
+///
+/// ## Generic arguments
+///
+/// As with `VecLit`, a literal of a generic data structure can start with `::<...>` to give its type arguments. Type parameters with a default take it unless they are given, as they would in a type.
+/// ```
+/// # use derive_lit::MapLit;
+/// use std::collections::hash_map::{DefaultHasher, HashMap, RandomState};
+/// use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+///
+/// #[derive(MapLit)]
+/// struct MyMap<K, V, S = RandomState>(HashMap<K, V, S>);
+/// # impl<K: Hash + Eq, V, S: BuildHasher + Default> MyMap<K, V, S> { fn new() -> Self {Self(HashMap::default())} fn insert(&mut self, key: K, val: V) {self.0.insert(key, val);}}
+///
+/// let x = my_map! { "a" => 1 };
+/// let y = my_map! { ::<_, _, BuildHasherDefault<DefaultHasher>> "a" => 1 };
+/// ```
+///
+/// # Options
+///
+/// Besides the options documented on [`VecLit`], map-like data structures take the following ones.
+///
+/// ## `on_duplicate`
+///
+/// `#[lit(on_duplicate = "...")]` decides what map-like literals do when a key is inserted twice. With `"last"`, the default, the later value wins. With `"first"`, entries whose key is already in the map are skipped, which requires `fn contains_key(&key) -> bool`. With `"panic"`, the literal panics with the key's `Debug` output. It applies to `MapLit` and `VersionMapLit`, while `HistogramLit` and `CacheLit` pass every entry on to `record` and `insert`.
+/// ```should_panic
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// #[derive(MapLit)]
+/// #[lit(on_duplicate = "panic")]
+/// struct Lookup(HashMap<&'static str, usize>);
+/// # impl Lookup { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: &'static str, val: usize) {self.0.insert(key, val);} fn contains_key(&self, key: &&'static str) -> bool {self.0.contains_key(key)}}
+///
+/// let x: Lookup = lookup! {
+///     "a" => 0,
+///     "a" => 7
+/// };
+/// ```
+///
+/// ## `fallback`
+///
+/// `#[lit(fallback = "method")]` lets map-like literals end with a `_ => val` entry, which calls `method(val)` after the other entries are inserted. This is for lookup tables with a default case.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// #[derive(MapLit)]
+/// #[lit(fallback = "set_default")]
+/// struct Lookup {
+///     entries: HashMap<&'static str, usize>,
+///     default: usize
+/// }
+/// # impl Lookup { fn new() -> Self {Self{entries: HashMap::new(), default: 0}} fn insert(&mut self, key: &'static str, val: usize) {self.entries.insert(key, val);} fn set_default(&mut self, val: usize) {self.default = val}}
+///
+/// let x: Lookup = lookup! {
+///     "a" => 0,
+///     "b" => 7,
+///     _ => 1
+/// };
+/// assert_eq!(x.default, 1);
+/// ```
+///
+/// ## `key_into` and `value_into`
+///
+/// `#[lit(key_into)]` converts the keys of map-like literals with `Into`, and `#[lit(value_into)]` does the same for their values, independently of each other. This is handy for a map with `String` keys, which can then be written as string literals. Spread entries and `from` iterators are inserted as they are.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// #[derive(MapLit)]
+/// #[lit(key_into)]
+/// struct Vars(HashMap<String, u16>);
+/// # impl Vars { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: String, val: u16) {self.0.insert(key, val);}}
+///
+/// let x = vars! { "PORT" => 8080, "WORKERS" => 4 };
+/// assert_eq!(x.0[&"PORT".to_string()], 8080);
+/// ```
+///
+/// ## `own_keys`
+///
+/// `#[lit(own_keys)]` converts the string literal keys of map-like literals with `to_owned`, so a literal of a map with `String` keys can have keys like `"a"` without a `.to_string()` on each. Unlike with `key_into`, keys that aren't string literals are inserted as they are.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// #[derive(MapLit)]
+/// #[lit(own_keys)]
+/// struct Labels(HashMap<String, usize>);
+/// # impl Labels { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: String, val: usize) {self.0.insert(key, val);}}
+///
+/// let name = format!("user-{}", 7);
+/// let x = labels! { "admin" => 0, name => 7 };
+/// assert_eq!(x.0["user-7"], 7);
+/// ```
+///
+/// ## `key_wrap`
+///
+/// `#[lit(key_wrap = "Wrapper")]` wraps each key of map-like literals in `Wrapper(key)`, which can be a tuple struct or a function. This is for keys that can't be ordered or hashed as they are, like floats in an ordered map, which are then written plainly. As with `key_into`, spread entries and `from` iterators are inserted as they are, and a key is wrapped before it's converted.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::BTreeMap;
+/// #[derive(PartialEq, PartialOrd)]
+/// struct OrderedFloat(f64);
+/// # impl Eq for OrderedFloat {}
+/// # impl Ord for OrderedFloat { fn cmp(&self, other: &Self) -> std::cmp::Ordering {self.0.total_cmp(&other.0)} }
+///
+/// #[derive(MapLit)]
+/// #[lit(key_wrap = "OrderedFloat")]
+/// struct Thresholds(BTreeMap<OrderedFloat, &'static str>);
+/// # impl Thresholds { fn new() -> Self {Self(BTreeMap::new())} fn insert(&mut self, key: OrderedFloat, val: &'static str) {self.0.insert(key, val);}}
+///
+/// let x = thresholds! { 1.5 => "a", 0.5 => "b" };
+/// assert_eq!(x.0.values().collect::<Vec<_>>(), [&"b", &"a"]);
+/// ```
+///
+/// ## `composite_keys`
+///
+/// `#[lit(composite_keys)]` lets the entries of map-like literals with tuple keys be written `k1, k2 => val`, which inserts `val` with the key `(k1, k2)`. This makes multi-key lookup tables easier to write and read. Keys of the wrong arity are reported at their entry.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// #[derive(MapLit)]
+/// #[lit(composite_keys)]
+/// struct Greetings(HashMap<(&'static str, &'static str), &'static str>);
+/// # impl Greetings { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: (&'static str, &'static str), val: &'static str) {self.0.insert(key, val);}}
+///
+/// let x = greetings! {
+///     "us", "en" => "hello",
+///     "fr", "fr" => "bonjour",
+/// };
+/// assert_eq!(x.0[&("fr", "fr")], "bonjour");
+/// ```
+///
+/// ## `dotted`
+///
+/// `#[lit(dotted = "path::to::new")]` splits the string literal keys of map-like literals at their dots, so `"server.port" => 8080` inserts `"port" => 8080` into a nested map that's inserted under `"server"`. The entries under the same prefix share a nested map, constructed by the given function and filled the same way as the literal, which makes deep config-style maps writable as flat literals. The nested maps are inserted as values, so they usually need `value_into` to be converted to the value type along with the other values.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::BTreeMap;
+/// #[derive(Debug, PartialEq)]
+/// enum Value {
+///     Str(&'static str),
+///     Int(u16),
+///     Map(Config),
+/// }
+/// # impl From<&'static str> for Value { fn from(s: &'static str) -> Self {Value::Str(s)} }
+/// # impl From<u16> for Value { fn from(n: u16) -> Self {Value::Int(n)} }
+/// # impl From<Config> for Value { fn from(map: Config) -> Self {Value::Map(map)} }
+///
+/// #[derive(MapLit, Debug, PartialEq)]
+/// #[lit(dotted = "Config::new", value_into)]
+/// struct Config(BTreeMap<&'static str, Value>);
+/// # impl Config { fn new() -> Self {Self(BTreeMap::new())} fn insert(&mut self, key: &'static str, val: Value) {self.0.insert(key, val);}}
+///
+/// let x = config! { "name" => "demo", "server.host" => "localhost", "server.port" => 8080 };
+/// assert_eq!(x.0["server"], Value::Map(config! { "host" => "localhost", "port" => 8080 }));
+/// ```
+///
+/// Since the nested map is inserted where its first key was written, the entries under a prefix can't be split by a `let` or a `..map` spread, which the later ones would be moved in front of.
+/// ```compile_fail
+/// # use derive_lit::MapLit;
+/// # use std::collections::BTreeMap;
+/// #[derive(MapLit)]
+/// #[lit(dotted = "Config::new", value_into)]
+/// struct Config(BTreeMap<&'static str, u16>);
+/// # impl Config { fn new() -> Self {Self(BTreeMap::new())} fn insert(&mut self, key: &'static str, val: u16) {self.0.insert(key, val);}}
+/// # impl From<Config> for u16 { fn from(_: Config) -> Self {0} }
+///
+/// let x = config! { "server.host" => 1u16, let port = 8080u16; "server.port" => port };
+/// ```
+///
+/// ## `multi`
+///
+/// `#[lit(multi)]` is for map-like data structures that hold several values per key. Their literals accept `key => ..iter` entries, which insert every item of `iter` under a clone of `key`.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// #[derive(MapLit)]
+/// #[lit(multi)]
+/// struct Tags(HashMap<&'static str, Vec<&'static str>>);
+/// # impl Tags { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: &'static str, val: &'static str) {self.0.entry(key).or_default().push(val)}}
+///
+/// let imported = vec!["b", "c"];
+/// let x = tags! { "post" => "a", "post" => ..imported, "page" => "d" };
+/// assert_eq!(x.0["post"], vec!["a", "b", "c"]);
+/// ```
+///
+/// ## `section`
+///
+/// `#[lit(section(label = "method"))]` replaces the usual literal syntax with labeled sections, each added to the data structure through its own method. A section is either a list of elements in `[...]` or a list of `key => val` entries in `{...}`. The attribute can be given several times, and the sections can be written in any order.
+/// ```
+/// # use derive_lit::MapLit;
+/// #[derive(MapLit)]
+/// #[lit(section(headers = "add_header"), section(params = "add_param"))]
+/// struct Request {
+///     headers: Vec<(&'static str, &'static str)>,
+///     params: Vec<u32>
+/// }
+/// # impl Request { fn new() -> Self {Self{headers: vec![], params: vec![]}} fn add_header(&mut self, key: &'static str, val: &'static str) {self.headers.push((key, val))} fn add_param(&mut self, param: u32) {self.params.push(param)}}
+///
+/// let req = request! {
+///     params: [1, 2],
+///     headers: { "Accept" => "text/html" }
+/// };
+/// assert_eq!(req.params, vec![1, 2]);
+/// ```
+///
+/// ## `from_pairs`
+///
+/// `#[lit(from_pairs = "K => V")]` generates `fn from_pairs(pairs: impl IntoIterator<Item = (K, V)>) -> Self` for map-like data structures alongside the macro, so data loaded at runtime is inserted the same way as the entries of a literal, following options like `on_duplicate`, `key_into` and `value_into`.
+/// ```
+/// # use derive_lit::MapLit;
+/// # use std::collections::HashMap;
+/// #[derive(MapLit)]
+/// #[lit(from_pairs = "String => u16")]
+/// struct Ports(HashMap<String, u16>);
+/// # impl Ports { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: String, val: u16) {self.0.insert(key, val);}}
+///
+/// let loaded = vec![("http".to_string(), 80), ("https".to_string(), 443)];
+/// let x = Ports::from_pairs(loaded);
+/// assert_eq!(x.0["https"], 443);
+/// ```
+#[proc_macro_derive(MapLit, attributes(lit))]
+pub fn derive_map_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::Map))
+}
+
+/// A derive for auto-generating a macro to create literal values for sparse matrices from `(row, col) => val` triplets
+///
+/// The sparse matrix must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn insert(row, col, val)`
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::SparseMatrixLit;
+/// # #[derive(SparseMatrixLit)]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn insert(&mut self, row: usize, col: usize, val: f64) {}}
+/// let x: MyStruct = my_struct! {
+///     (0, 0) => 1.0,
+///     (5, 2) => -3.0
+/// };
+/// ```
+///
+/// With `#[lit(blocks = "method")]`, a literal can instead compose the matrix from blocks, written like `[a, b; c, d]` with the rows separated by `;`. Each block is an expression, like another literal, and is set with `method(row, col, block)` at its row and column among the blocks. The rows are checked at compile time to have the same number of blocks.
+/// ```
+/// # use derive_lit::SparseMatrixLit;
+/// #[derive(SparseMatrixLit)]
+/// #[lit(blocks = "set_block")]
+/// struct Mat2(Vec<(usize, usize, f64)>);
+/// # impl Mat2 { fn new() -> Self {Self(vec![])} fn insert(&mut self, row: usize, col: usize, val: f64) {self.0.push((row, col, val))} fn set_block(&mut self, row: usize, col: usize, block: Mat2) {self.0.extend(block.0.into_iter().map(|(r, c, val)| (row * 2 + r, col * 2 + c, val)))}}
+///
+/// let identity = mat2! { (0, 0) => 1.0, (1, 1) => 1.0 };
+/// let x = mat2! { [identity, mat2! {}; mat2! {}, mat2! { (0, 1) => 5.0 }] };
+/// assert_eq!(x.0, vec![(0, 0, 1.0), (1, 1, 1.0), (2, 3, 5.0)]);
+/// ```
+/// ```compile_fail
+/// # use derive_lit::SparseMatrixLit;
+/// # #[derive(SparseMatrixLit)]
+/// # #[lit(blocks = "set_block")]
+/// # struct Mat2(Vec<(usize, usize, f64)>);
+/// # impl Mat2 { fn new() -> Self {Self(vec![])} fn insert(&mut self, row: usize, col: usize, val: f64) {self.0.push((row, col, val))} fn set_block(&mut self, row: usize, col: usize, block: Mat2) {self.0.extend(block.0.into_iter().map(|(r, c, val)| (row * 2 + r, col * 2 + c, val)))}}
+/// // every row of blocks in a literal must have the same number of blocks
+/// let x = mat2! { [mat2! {}, mat2! {}; mat2! {}] };
+/// ```
+#[proc_macro_derive(SparseMatrixLit, attributes(lit))]
+pub fn derive_sparse_matrix_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::SparseMatrix))
+}
+
+/// A derive for auto-generating a macro to create literal values for histograms from `bucket => count` entries
+///
+/// The histogram must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn record(bucket, count)`
+///
+/// With `#[lit(contiguous)]`, buckets whose bounds are numeric literals are checked at compile time to be contiguous and non-overlapping in the order they're written.
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::HistogramLit;
+/// # use std::ops::Range;
+/// # #[derive(HistogramLit)]
+/// # #[lit(contiguous)]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn record(&mut self, bucket: Range<usize>, count: usize) {}}
+/// let x: MyStruct = my_struct! {
+///     0..10 => 4,
+///     10..20 => 9
+/// };
+/// ```
+#[proc_macro_derive(HistogramLit, attributes(lit))]
+pub fn derive_histogram_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::Histogram))
+}
+
+/// A derive for auto-generating a macro to create literal values for weighted samplers from `elem: weight` entries
+///
+/// The sampler must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn add(elem, weight)`
+///
+/// With `#[lit(weight_sum = 100)]`, literals whose weights are all numeric literals are checked at compile time to sum to the given total.
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::SamplerLit;
+/// # #[derive(SamplerLit)]
+/// # #[lit(weight_sum = 100)]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn add(&mut self, elem: &'static str, weight: u32) {}}
+/// let x: MyStruct = my_struct! {
+///     "common": 80,
+///     "rare": 19,
+///     "epic": 1
+/// };
+/// ```
+///
+/// Weights that sum to anything else fail to compile.
+/// ```compile_fail
+/// # use derive_lit::SamplerLit;
+/// #[derive(SamplerLit)]
+/// #[lit(weight_sum = 100)]
+/// struct Loot(Vec<(&'static str, u32)>);
+/// # impl Loot { fn new() -> Self {Self(vec![])} fn add(&mut self, elem: &'static str, weight: u32) {self.0.push((elem, weight))}}
+///
+/// // weights sum to 99, expected 100
+/// let x = loot! { "common": 80, "rare": 19 };
+/// ```
+///
+/// An element ends at the first `:` that follows a whole expression, so elements can have colons of their own, like the typed parameter of a closure.
+/// ```
+/// # use derive_lit::SamplerLit;
+/// #[derive(SamplerLit)]
+/// struct Rolls(Vec<(u64, u32)>);
+/// # impl Rolls { fn new() -> Self {Self(vec![])} fn add(&mut self, roll: impl Fn(u64) -> u64, weight: u32) {self.0.push((roll(7), weight))}}
+///
+/// let x = rolls! { |seed: u64| seed % 6: 5, |_| 6: 1 };
+/// assert_eq!(x.0, [(1, 5), (6, 1)]);
+/// ```
+#[proc_macro_derive(SamplerLit, attributes(lit))]
+pub fn derive_sampler_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
 
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::Sampler))
+}
 
-/// A derive for auto-generating a macro to create literal values for vec-like data structures
+/// A derive for auto-generating a macro to create literal values for bounded caches from a capacity and `key => val` entries
 ///
-/// The vec-like data structure must have the following methods-
-/// - `fn new() -> Self`
-/// - `fn push(elem)`
+/// The cache must have the following methods-
+/// - `fn with_capacity(cap) -> Self`
+/// - `fn insert(key, val)`
 ///
 /// The auto-generated macro will be of the following form-
 /// ```
-/// # use derive_lit::VecLit;
-/// # #[derive(VecLit)]
+/// # use derive_lit::CacheLit;
+/// # #[derive(CacheLit)]
 /// # struct MyStruct;
-/// # impl MyStruct { fn new() -> Self {Self{}} fn push(&mut self, elem: usize) {}}
-/// let x: MyStruct = my_struct! [0, 9, 3, 4, 5];
+/// # impl MyStruct { fn with_capacity(cap: usize) -> Self {Self{}} fn insert(&mut self, key: &'static str, val: usize) {}}
+/// let x: MyStruct = my_struct! {
+///     128;
+///     "a" => 1,
+///     "b" => 2
+/// };
 /// ```
-#[proc_macro_derive(VecLit)]
-pub fn derive_vec_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+#[proc_macro_derive(CacheLit, attributes(lit))]
+pub fn derive_cache_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    let name = input.ident;
-    let data = input.data;
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::Cache))
+}
 
-    let macro_name = Ident::new(&name.to_string().to_snake_case(), name.span());
-    let struct_name = name.clone();
+/// A derive for auto-generating a macro to create literal values for version-keyed maps from `version => val` entries
+///
+/// The map must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn insert(key, val)`
+///
+/// Keys that are literals are checked at compile time to be semver versions like `"1.2.3"` or `"2.0.0-rc.1"`, while keys that aren't literals are inserted unchecked. With `#[lit(version = "path::to::ctor")]`, the checked keys are converted to `ctor(major, minor, patch)` instead of being inserted as strings, which rules out a pre-release or build.
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::VersionMapLit;
+/// # #[derive(VersionMapLit)]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn insert(&mut self, key: &'static str, val: usize) {}}
+/// let x: MyStruct = my_struct! {
+///     "1.0.0" => 1,
+///     "2.0.0-rc.1" => 2
+/// };
+/// ```
+///
+/// A literal key that isn't a version fails to compile.
+/// ```compile_fail
+/// # use derive_lit::VersionMapLit;
+/// #[derive(VersionMapLit)]
+/// struct Releases(Vec<(&'static str, u32)>);
+/// # impl Releases { fn new() -> Self {Self(vec![])} fn insert(&mut self, key: &'static str, val: u32) {self.0.push((key, val))}}
+///
+/// // invalid version: expected `major.minor.patch`, found `1.0`
+/// let x = releases! { "1.0" => 1 };
+/// ```
+#[proc_macro_derive(VersionMapLit, attributes(lit))]
+pub fn derive_version_map_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
 
-    if let Data::Struct(_) = data {
-    } else {
-        // TODO throw error
-        panic!("expected a struct")
-    }
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::VersionMap))
+}
 
-    let expanded = quote! {
-        macro_rules! #macro_name {
-            ( $( $elem:expr ),* ) => {
-                {
-                    let mut temp = #struct_name::new();
-                    $(
-                        temp.push($elem);
-                    )*
-                    temp
-                }
-            };	
-        }
-    };
+/// A derive for auto-generating a macro to create literal values for directory trees from `name => contents` files and `name => { ... }` directories
+///
+/// The tree must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn add_dir(path)`
+/// - `fn add_file(path, contents)`
+///
+/// Names are string literals, and nested directories are flattened at compile time into full paths joined with `/`, so `"src" => { "main.rs" => "..." }` adds the directory `"src"` and then the file `"src/main.rs"`. Each directory is added before the entries inside it. This suits setting up test fixtures on a virtual filesystem.
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::DirTreeLit;
+/// # #[derive(DirTreeLit)]
+/// # struct MyStruct(Vec<String>);
+/// # impl MyStruct { fn new() -> Self {Self(vec![])} fn add_dir(&mut self, path: &str) {self.0.push(format!("{}/", path))} fn add_file(&mut self, path: &str, contents: &str) {self.0.push(path.to_string())}}
+/// let x: MyStruct = my_struct! {
+///     "src" => {
+///         "main.rs" => "fn main() {}",
+///         "lib.rs" => ""
+///     },
+///     "Cargo.toml" => "[package]"
+/// };
+/// # assert_eq!(x.0, vec!["src/", "src/main.rs", "src/lib.rs", "Cargo.toml"]);
+/// ```
+#[proc_macro_derive(DirTreeLit, attributes(lit))]
+pub fn derive_dir_tree_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
 
     // hand the output tokens back to the compiler.
-    proc_macro::TokenStream::from(expanded)
+    with_notes(derive_lit_core::derive(input, Kind::DirTree))
 }
 
-/// A derive for auto-generating a macro to create literal values for vec-like data structures with a front at right end
+/// A derive for auto-generating a macro to create channels already holding a list of elements
 ///
-/// The vec-like data structure must have the following methods-
-/// - `fn new() -> Self`
-/// - `fn push_front(elem)`
+/// The type must have the following methods-
+/// - `fn new() -> (tx, rx)`, making a channel
+///
+/// And the sender must have the following method-
+/// - `fn send(elem) -> Result<_, _>`
+///
+/// The literal makes the channel, sends each element in order and evaluates to its `(tx, rx)` handles, which suits seeding the queue of a worker in tests. With `#[lit(sender)]`, `new()` returns only the sender, which the literal then evaluates to. The literal panics if the receiver is disconnected, and a bounded channel must have room for all of the elements, or sending blocks.
 ///
 /// The auto-generated macro will be of the following form-
 /// ```
-/// # use derive_lit::VecFrontLit;
-/// # #[derive(VecFrontLit)]
+/// # use derive_lit::ChannelLit;
+/// # use std::sync::mpsc::{self, Receiver, Sender};
+/// # #[derive(ChannelLit)]
 /// # struct MyStruct;
-/// # impl MyStruct { fn new() -> Self {Self{}} fn push_front(&mut self, elem: usize) {}}
-/// let x: MyStruct = my_struct! [0, 9, 3, 4, 5]; // front at right
+/// # impl MyStruct { fn new() -> (Sender<u32>, Receiver<u32>) {mpsc::channel()}}
+/// let (tx, rx) = my_struct![1, 2, 3];
+/// # drop(tx);
+/// # assert_eq!(rx.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
 /// ```
-#[proc_macro_derive(VecFrontLit)]
-pub fn derive_vec_front_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+#[proc_macro_derive(ChannelLit, attributes(lit))]
+pub fn derive_channel_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    let name = input.ident;
-    let data = input.data;
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::Channel))
+}
 
-    let macro_name = Ident::new(&name.to_string().to_snake_case(), name.span());
-    let struct_name = name.clone();
+/// A derive for auto-generating a macro to create literal values for segment trees and Fenwick trees from their leaves
+///
+/// The tree must have the following methods-
+/// - `fn new(len: usize) -> Self`
+/// - `fn update(index: usize, leaf)`
+///
+/// The literal constructs the tree with its number of leaves, then updates each index with its leaf in order. With `#[lit(const_len)]`, the number of leaves is also given as the const parameter of a tree whose length is part of its type, like `SegmentTree<const N: usize>`.
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::SegmentTreeLit;
+/// #[derive(SegmentTreeLit)]
+/// struct Fenwick(Vec<i64>);
+/// # impl Fenwick { fn new(len: usize) -> Self {Self(vec![0; len + 1])} fn update(&mut self, index: usize, delta: i64) {let mut i = index + 1; while i < self.0.len() {self.0[i] += delta; i += i & i.wrapping_neg();}} fn prefix_sum(&self, len: usize) -> i64 {let (mut i, mut sum) = (len, 0); while i > 0 {sum += self.0[i]; i -= i & i.wrapping_neg();} sum}}
+///
+/// let x = fenwick![1, 5, 3, 7];
+/// assert_eq!(x.prefix_sum(3), 9);
+///
+/// #[derive(SegmentTreeLit)]
+/// #[lit(const_len)]
+/// struct MaxTree<const N: usize>([u32; N]);
+/// # impl<const N: usize> MaxTree<N> { fn new(len: usize) -> Self {assert_eq!(len, N); Self([0; N])} fn update(&mut self, index: usize, leaf: u32) {self.0[index] = leaf}}
+///
+/// let y = max_tree![4, 8, 2];
+/// let _: MaxTree<3> = y;
+/// ```
+#[proc_macro_derive(SegmentTreeLit, attributes(lit))]
+pub fn derive_segment_tree_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
 
-    if let Data::Struct(_) = data {
-    } else {
-        // TODO throw error
-        panic!("expected a struct")
-    }
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::SegmentTree))
+}
 
-    let expanded = quote! {
-        macro_rules! #macro_name {
-            ( $( $elem:expr ),* ) => {
-                {
-                    let mut temp = #struct_name::new();
-                    $(
-                        temp.push_front($elem);
-                    )*
-                    temp
-                }
-            };	
-        }
-    };
+/// A derive for auto-generating a macro to create literal values for union-find data structures from groups of elements
+///
+/// The union-find data structure must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn make_set(elem)`
+/// - `fn union(elem, elem)`
+///
+/// Each group written in `{...}` makes a set of each of its elements, then unions each element with the one before it, so the group ends up as one set. The elements must implement `Clone`.
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::UnionFindLit;
+/// # #[derive(UnionFindLit)]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn make_set(&mut self, elem: usize) {} fn union(&mut self, a: usize, b: usize) {}}
+/// let x: MyStruct = my_struct! { {1, 2, 3}, {4, 5}, {6} };
+/// ```
+#[proc_macro_derive(UnionFindLit, attributes(lit))]
+pub fn derive_union_find_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
 
     // hand the output tokens back to the compiler.
-    proc_macro::TokenStream::from(expanded)
+    with_notes(derive_lit_core::derive(input, Kind::UnionFind))
 }
 
-/// A derive for auto-generating a macro to create literal values for set-like data structures
+/// A derive for auto-generating a macro to create literal values for binary trees from their nested nodes
 ///
-/// The set-like data structure must have the following methods-
+/// The binary tree must have the following methods-
 /// - `fn new() -> Self`
-/// - `fn insert(elem)`
+/// - `fn node(left: Self, value, right: Self) -> Self`
+///
+/// The literal is the root of the tree, where a node is written as `(left, value, right)` or as `value { left, right }`, a subtree of `_` is empty, and any other value is a node whose subtrees are both empty. The nesting is expanded into calls of `node`, with `new()` making each empty subtree, so a struct literal as a value is written in parentheses. An empty literal is the empty tree.
 ///
 /// The auto-generated macro will be of the following form-
 /// ```
-/// # use derive_lit::SetLit;
-/// # #[derive(SetLit)]
+/// # use derive_lit::BinaryTreeLit;
+/// # #[derive(BinaryTreeLit)]
 /// # struct MyStruct;
-/// # impl MyStruct { fn new() -> Self {Self{}} fn insert(&mut self, elem: usize) {}}
-/// let x: MyStruct = my_struct! {0, 9, 3, 4, 5};
+/// # impl MyStruct { fn new() -> Self {Self{}} fn node(left: Self, value: i32, right: Self) -> Self {Self{}}}
+/// let x: MyStruct = my_struct!((1, 2, (_, 3, _)));
+/// let y: MyStruct = my_struct!(2 { 1, 3 { _, 4 } });
 /// ```
-#[proc_macro_derive(SetLit)]
-pub fn derive_set_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+///
+/// The nodes are built left to right, so an in-order walk visits the values as they're written.
+/// ```
+/// # use derive_lit::BinaryTreeLit;
+/// #[derive(BinaryTreeLit)]
+/// struct Tree(Option<Box<(Tree, i32, Tree)>>);
+///
+/// impl Tree {
+///     fn new() -> Self {
+///         Tree(None)
+///     }
+///
+///     fn node(left: Self, value: i32, right: Self) -> Self {
+///         Tree(Some(Box::new((left, value, right))))
+///     }
+///
+///     fn in_order(&self, values: &mut Vec<i32>) {
+///         if let Some(node) = &self.0 {
+///             node.0.in_order(values);
+///             values.push(node.1);
+///             node.2.in_order(values);
+///         }
+///     }
+/// }
+///
+/// let mut values = vec![];
+/// tree!(4 { (1, 2, 3), (_, 5, -6) }).in_order(&mut values);
+/// assert_eq!(values, [1, 2, 3, 4, 5, -6]);
+///
+/// assert!(tree!(_).0.is_none());
+/// ```
+#[proc_macro_derive(BinaryTreeLit, attributes(lit))]
+pub fn derive_binary_tree_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    let name = input.ident;
-    let data = input.data;
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::BinaryTree))
+}
 
-    let macro_name = Ident::new(&name.to_string().to_snake_case(), name.span());
-    let struct_name = name.clone();
+/// A derive for auto-generating a macro to create literal values for ropes and other text buffers from string chunks
+///
+/// The rope must have the following methods-
+/// - `fn new() -> Self`
+/// - `fn push_str(&mut self, chunk: &str)`
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::RopeLit;
+/// # #[derive(RopeLit)]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn push_str(&mut self, chunk: &str) {}}
+/// let tail = String::from("the end");
+/// let x: MyStruct = my_struct!["line one\n", include_str!("../Cargo.toml"), &tail];
+/// ```
+///
+/// With `#[lit(split_lines)]`, a string literal chunk of several lines is pushed line by line, each with its `\n`, which suits ropes that balance around line breaks. The lines are split at compile time, so it's no slower than pushing them by hand.
+/// ```
+/// # use derive_lit::RopeLit;
+/// #[derive(RopeLit)]
+/// #[lit(split_lines)]
+/// struct Rope(Vec<String>);
+/// # impl Rope { fn new() -> Self {Self(vec![])} fn push_str(&mut self, chunk: &str) {self.0.push(chunk.to_string())}}
+///
+/// let x = rope!["fn main() {\n    println!();\n}\n", "// done"];
+/// assert_eq!(x.0, ["fn main() {\n", "    println!();\n", "}\n", "// done"]);
+/// ```
+#[proc_macro_derive(RopeLit, attributes(lit))]
+pub fn derive_rope_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
 
-    if let Data::Struct(_) = data {
-    } else {
-        // TODO throw error
-        panic!("expected a struct")
-    }
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::Rope))
+}
 
-    let expanded = quote! {
-        macro_rules! #macro_name {
-            ( $( $elem:expr ),* ) => {
-                {
-                    let mut temp = #struct_name::new();
-                    $(
-                        temp.insert($elem);
-                    )*
-                    temp
-                }
-            };	
-        }
-    };
+/// A derive for auto-generating a macro to create literal values for struct-of-arrays containers from their rows
+///
+/// The container must have the following methods-
+/// - `fn new() -> Self`
+/// - a push method for each column, like `fn push_x(&mut self, x)`
+///
+/// `#[lit(columns = "push_x, push_y")]` names the push method of each column, in order. Each row is written as a tuple with a field for every column, which is destructured at compile time into a push of each field onto its column, so the container never holds the rows themselves. `from iter` pushes the fields of each tuple of `iter` the same way.
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::SoaLit;
+/// # #[derive(SoaLit)]
+/// # #[lit(columns = "push_x, push_y, push_z")]
+/// # struct MyStruct;
+/// # impl MyStruct { fn new() -> Self {Self{}} fn push_x(&mut self, x: f32) {} fn push_y(&mut self, y: f32) {} fn push_z(&mut self, z: f32) {}}
+/// let x: MyStruct = my_struct![(1.0, 2.0, 3.0), (4.0, 5.0, 6.0)];
+/// let y: MyStruct = my_struct![from vec![(0.0, 0.0, 0.0)]];
+/// ```
+///
+/// ```
+/// # use derive_lit::SoaLit;
+/// #[derive(SoaLit)]
+/// #[lit(columns = "push_name, push_score")]
+/// struct Scores {
+///     names: Vec<&'static str>,
+///     scores: Vec<u32>,
+/// }
+/// # impl Scores { fn new() -> Self {Self{names: vec![], scores: vec![]}} fn push_name(&mut self, name: &'static str) {self.names.push(name)} fn push_score(&mut self, score: u32) {self.scores.push(score)}}
+///
+/// let x = scores![("ada", 90), ("grace", 85)];
+/// assert_eq!(x.names, ["ada", "grace"]);
+/// assert_eq!(x.scores, [90, 85]);
+/// ```
+#[proc_macro_derive(SoaLit, attributes(lit))]
+pub fn derive_soa_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
 
     // hand the output tokens back to the compiler.
-    proc_macro::TokenStream::from(expanded)
+    with_notes(derive_lit_core::derive(input, Kind::Soa))
 }
 
-/// A derive for auto-generating a macro to create literal values for map-like data structures
+/// A derive for auto-generating a macro to create literal values for ECS worlds from their entities' components
 ///
-/// The map-like data structure must have the following methods-
+/// The world must have the following methods-
 /// - `fn new() -> Self`
-/// - `fn insert(key, val)`
+/// - `fn spawn(&mut self) -> Entity`
+/// - `fn insert(&mut self, entity: Entity, component)`
+///
+/// Each entity is written as a list of its components in `[...]`, and is spawned before each of its components is inserted for it, in the order they're written. The entity type returned by `spawn` must implement `Clone`, and `insert` is usually generic over the component type.
 ///
 /// The auto-generated macro will be of the following form-
 /// ```
-/// # use derive_lit::MapLit;
-/// # #[derive(MapLit)]
+/// # use derive_lit::WorldLit;
+/// # #[derive(WorldLit)]
 /// # struct MyStruct;
-/// # impl MyStruct { fn new() -> Self {Self{}} fn insert(&mut self, key: &'static str, val: usize) {}}
-/// let x: MyStruct = my_struct! {
-///     "a" => 0,
-///     "b" => 0,
-///     "c" => 7
+/// # struct Position(i32, i32);
+/// # impl MyStruct { fn new() -> Self {Self{}} fn spawn(&mut self) -> u32 {0} fn insert(&mut self, entity: u32, component: Position) {}}
+/// let x: MyStruct = my_struct! { [Position(0, 0)], [Position(5, 5)], [] };
+/// ```
+///
+/// ```
+/// # use derive_lit::WorldLit;
+/// use std::any::Any;
+///
+/// struct Position(i32, i32);
+/// struct Velocity(i32, i32);
+///
+/// #[derive(WorldLit)]
+/// struct World {
+///     entities: u32,
+///     components: Vec<(u32, Box<dyn Any>)>,
+/// }
+///
+/// impl World {
+///     fn new() -> Self {
+///         World { entities: 0, components: vec![] }
+///     }
+///
+///     fn spawn(&mut self) -> u32 {
+///         self.entities += 1;
+///         self.entities - 1
+///     }
+///
+///     fn insert<C: Any>(&mut self, entity: u32, component: C) {
+///         self.components.push((entity, Box::new(component)));
+///     }
+/// }
+///
+/// let x = world! {
+///     [Position(0, 0), Velocity(1, 1)],
+///     [Position(5, 5)],
 /// };
+/// assert_eq!(x.entities, 2);
+/// assert!(x.components[1].1.is::<Velocity>());
+/// assert_eq!(x.components[2].0, 1);
 /// ```
-#[proc_macro_derive(MapLit)]
-pub fn derive_map_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+#[proc_macro_derive(WorldLit, attributes(lit))]
+pub fn derive_world_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    let name = input.ident;
-    let data = input.data;
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive(input, Kind::World))
+}
 
-    let macro_name = Ident::new(&name.to_string().to_snake_case(), name.span());
-    let struct_name = name.clone();
+/// A derive for auto-generating a macro to create literal values for data structures of any kind
+///
+/// `#[lit(kind = "...")]` names the kind of data structure, which needs the methods of its own derive, and the macro is generated like its own derive would. The kinds are `vec` for `VecLit`, `front` for `VecFrontLit`, `set`, `map`, `sparse_matrix`, `histogram`, `sampler`, `cache`, `version_map`, `dir_tree`, `channel`, `segment_tree`, `union_find`, `binary_tree`, `rope`, `soa` and `world`, and the other options can be given alongside the kind. The variants of an `EnumLit` can name their kind this way too.
+///
+/// The auto-generated macro will be of the following form-
+/// ```
+/// # use derive_lit::Lit;
+/// # use std::collections::HashMap;
+/// #[derive(Lit)]
+/// #[lit(kind = "map", own_keys)]
+/// struct Scores(HashMap<String, u32>);
+/// # impl Scores { fn new() -> Self {Self(HashMap::new())} fn insert(&mut self, key: String, val: u32) {self.0.insert(key, val);}}
+///
+/// let x = scores! { "a" => 1, "b" => 2 };
+/// # assert_eq!(x.0["b"], 2);
+/// ```
+#[proc_macro_derive(Lit, attributes(lit))]
+pub fn derive_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
 
-    if let Data::Struct(_) = data {
-    } else {
-        // TODO throw error
-        panic!("expected a struct")
-    }
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive_any(input))
+}
+
+/// A derive for auto-generating macros to create literal values for enum variants wrapping data structures
+///
+/// Each variant with a single unnamed field can name the derive of its field's data structure in `#[lit(...)]`, along with the options for it. Its macro is named after the enum and the variant, and builds the data structure like the named derive's macro would before wrapping it in the variant.
+///
+/// The auto-generated macros will be of the following form-
+/// ```
+/// # use derive_lit::EnumLit;
+/// # use std::collections::HashMap;
+/// #[derive(EnumLit)]
+/// enum Payload {
+///     #[lit(VecLit)]
+///     List(Vec<u8>),
+///     #[lit(MapLit, own_keys)]
+///     Map(HashMap<String, u8>),
+///     Empty,
+/// }
+///
+/// let x = payload_list![0, 9, 8];
+/// let y = payload_map! { "a" => 1 };
+/// # assert!(matches!(x, Payload::List(list) if list == vec![0, 9, 8]));
+/// # assert!(matches!(y, Payload::Map(map) if map["a"] == 1));
+/// ```
+#[proc_macro_derive(EnumLit, attributes(lit))]
+pub fn derive_enum_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    // hand the output tokens back to the compiler.
+    with_notes(derive_lit_core::derive_enum(input))
+}
+
+/// A macro for auto-generating literal macros for many already-defined data structures at once
+///
+/// Each group names a derive followed by the data structures to generate its macro for. The data structures must have the same methods the derive requires.
+/// ```
+/// # use derive_lit::derive_lits;
+/// # struct Foo; impl Foo { fn new() -> Self {Self{}} fn push(&mut self, elem: usize) {}}
+/// # struct Bar; impl Bar { fn new() -> Self {Self{}} fn push(&mut self, elem: usize) {}}
+/// # struct Baz; impl Baz { fn new() -> Self {Self{}} fn insert(&mut self, key: &'static str, val: usize) {}}
+/// derive_lits! {
+///     VecLit for Foo, Bar;
+///     MapLit for Baz;
+/// }
+///
+/// let x: Foo = foo! [0, 9, 3];
+/// let y: Bar = bar! [4, 5];
+/// let z: Baz = baz! { "a" => 0 };
+/// ```
+#[proc_macro]
+pub fn derive_lits(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let batch = parse_macro_input!(input as derive_lit_core::Batch);
+
+    // hand the output tokens back to the compiler.
+    with_notes(batch.expand())
+}
+
+/// A macro for auto-generating a macro to create literal values for type aliases, which can't take a derive
+///
+/// The macro is given a name, the aliased type and the method used to add to it. Depending on the method, the generated macro accepts either a list of elements or a list of `key => val` entries.
+/// ```
+/// # use derive_lit::alias_lit;
+/// use std::collections::HashMap;
+///
+/// type Headers = HashMap<String, String>;
+///
+/// alias_lit!(headers: Headers -> insert);
+///
+/// let x: Headers = headers! {
+///     "Accept".to_string() => "text/html".to_string()
+/// };
+///
+/// type Ids = Vec<usize>;
+///
+/// alias_lit!(ids: Ids -> push);
+///
+/// let y: Ids = ids![0, in 5..7];
+/// assert_eq!(y, vec![0, 5, 6]);
+/// ```
+#[proc_macro]
+pub fn alias_lit(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let alias = parse_macro_input!(input as derive_lit_core::Alias);
+
+    // hand the output tokens back to the compiler.
+    with_notes(alias.expand())
+}
 
-    let expanded = quote! {
-        macro_rules! #macro_name(
-		    { $($key:expr => $val:expr),* } => {
-		        {
-		            let mut temp = #struct_name::new();
-		            $(
-		                temp.insert($key, $val);
-		            )*
-		            
-		            temp
-		        }
-		     };
-		);
-    };
-
-    // hand the output tokens back to the compiler.
-    proc_macro::TokenStream::from(expanded)
-}
\ No newline at end of file
+/// A ready-made literal macro for `HashMap`, with the `std_lits` feature
+///
+/// It accepts everything the macros of `MapLit` do.
+/// ```
+/// # #[cfg(feature = "std_lits")] {
+/// use derive_lit::hash_map;
+///
+/// let x = hash_map! { "a" => 1, "b" => 2 };
+/// assert_eq!(x["b"], 2);
+/// # }
+/// ```
+#[cfg(feature = "std_lits")]
+#[proc_macro]
+pub fn hash_map(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    // hand the output tokens back to the compiler.
+    proc_macro::TokenStream::from(derive_lit_core::StdLit::HashMap.expand(input.into()))
+}
+
+/// A ready-made literal macro for `BTreeMap`, with the `std_lits` feature
+///
+/// It accepts everything the macros of `MapLit` do.
+/// ```
+/// # #[cfg(feature = "std_lits")] {
+/// use derive_lit::btree_map;
+///
+/// let x = btree_map! { "b" => 2, "a" => 1 };
+/// assert_eq!(x.keys().collect::<Vec<_>>(), vec![&"a", &"b"]);
+/// # }
+/// ```
+#[cfg(feature = "std_lits")]
+#[proc_macro]
+pub fn btree_map(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    // hand the output tokens back to the compiler.
+    proc_macro::TokenStream::from(derive_lit_core::StdLit::BTreeMap.expand(input.into()))
+}
+
+/// A ready-made literal macro for `HashSet`, with the `std_lits` feature
+///
+/// It accepts everything the macros of `SetLit` do.
+/// ```
+/// # #[cfg(feature = "std_lits")] {
+/// use derive_lit::hash_set;
+///
+/// let x = hash_set![0, 9, 0];
+/// assert_eq!(x.len(), 2);
+/// # }
+/// ```
+#[cfg(feature = "std_lits")]
+#[proc_macro]
+pub fn hash_set(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    // hand the output tokens back to the compiler.
+    proc_macro::TokenStream::from(derive_lit_core::StdLit::HashSet.expand(input.into()))
+}
+
+/// A ready-made literal macro for `BTreeSet`, with the `std_lits` feature
+///
+/// It accepts everything the macros of `SetLit` do.
+/// ```
+/// # #[cfg(feature = "std_lits")] {
+/// use derive_lit::btree_set;
+///
+/// let x = btree_set![9, 0, in 3..5];
+/// assert_eq!(x.into_iter().collect::<Vec<_>>(), vec![0, 3, 4, 9]);
+/// # }
+/// ```
+#[cfg(feature = "std_lits")]
+#[proc_macro]
+pub fn btree_set(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    // hand the output tokens back to the compiler.
+    proc_macro::TokenStream::from(derive_lit_core::StdLit::BTreeSet.expand(input.into()))
+}
+
+/// A ready-made literal macro for `VecDeque`, with the `std_lits` feature
+///
+/// It accepts everything the macros of `VecLit` do, pushing the elements to the back.
+/// ```
+/// # #[cfg(feature = "std_lits")] {
+/// use derive_lit::deque;
+///
+/// let x = deque![cap = 8; 0, 9];
+/// assert_eq!(x.back(), Some(&9));
+/// # }
+/// ```
+#[cfg(feature = "std_lits")]
+#[proc_macro]
+pub fn deque(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    // hand the output tokens back to the compiler.
+    proc_macro::TokenStream::from(derive_lit_core::StdLit::VecDeque.expand(input.into()))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __check_buckets(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let buckets = parse_macro_input!(input as derive_lit_core::Buckets);
+
+    proc_macro::TokenStream::from(buckets.check())
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __weighted(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let weighted = parse_macro_input!(input as derive_lit_core::Weighted);
+
+    proc_macro::TokenStream::from(weighted.expand())
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __elems(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let elems = parse_macro_input!(input as derive_lit_core::Elems);
+
+    proc_macro::TokenStream::from(elems.expand())
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __entries(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let entries = parse_macro_input!(input as derive_lit_core::Entries);
+
+    proc_macro::TokenStream::from(entries.expand())
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __count(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let count = parse_macro_input!(input as derive_lit_core::Count);
+
+    proc_macro::TokenStream::from(count.expand())
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __csv(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let csv = parse_macro_input!(input as derive_lit_core::Csv);
+
+    proc_macro::TokenStream::from(csv.expand())
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __dir_tree(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let tree = parse_macro_input!(input as derive_lit_core::DirTree);
+
+    proc_macro::TokenStream::from(tree.expand())
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __binary_tree(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let tree = parse_macro_input!(input as derive_lit_core::BinaryTree);
+
+    proc_macro::TokenStream::from(tree.expand())
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __sorted(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let sorted = parse_macro_input!(input as derive_lit_core::Sorted);
+
+    proc_macro::TokenStream::from(sorted.expand())
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __version(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let key = parse_macro_input!(input as derive_lit_core::VersionKey);
+
+    proc_macro::TokenStream::from(key.expand())
+}